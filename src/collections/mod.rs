@@ -0,0 +1,430 @@
+pub mod stack {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Stack<T> {
+        elements: Vec<T>,
+    }
+
+    impl<T> Stack<T> {
+        pub fn new() -> Self {
+            Self {
+                elements: Vec::new(),
+            }
+        }
+
+        pub fn with_capacity(capacity: usize) -> Self {
+            Self {
+                elements: Vec::with_capacity(capacity),
+            }
+        }
+
+        pub fn push(&mut self, item: T) {
+            self.elements.push(item);
+        }
+
+        pub fn pop(&mut self) -> Option<T> {
+            self.elements.pop()
+        }
+
+        pub fn peek(&self) -> Option<&T> {
+            self.elements.last()
+        }
+
+        pub fn len(&self) -> usize {
+            self.elements.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.elements.is_empty()
+        }
+
+        // From the top down, the order `pop` would return the items in
+        pub fn iter(&self) -> std::iter::Rev<std::slice::Iter<'_, T>> {
+            self.elements.iter().rev()
+        }
+    }
+
+    impl<T> Default for Stack<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Extend<T> for Stack<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            self.elements.extend(iter);
+        }
+    }
+
+    // The last item becomes the top
+    impl<T> FromIterator<T> for Stack<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            Self {
+                elements: iter.into_iter().collect(),
+            }
+        }
+    }
+
+    impl<T> IntoIterator for Stack<T> {
+        type Item = T;
+        type IntoIter = std::iter::Rev<std::vec::IntoIter<T>>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.elements.into_iter().rev()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a Stack<T> {
+        type Item = &'a T;
+        type IntoIter = std::iter::Rev<std::slice::Iter<'a, T>>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    pub fn run() {
+        let mut stack: Stack<i32> = (1..=3).collect();
+        stack.push(4);
+        println!("{:?}", stack);
+        println!("{:?} {:?}", stack.pop(), stack.peek());
+        println!("{:?}", stack.iter().collect::<Vec<&i32>>());
+    }
+}
+
+pub mod deque {
+    use std::fmt;
+
+    // Ring buffer: items live in `slots` starting at `head` and wrap around the end.
+    // The buffer doubles when full, unwrapping the items to the front of the new one.
+    #[derive(Clone)]
+    pub struct Deque<T> {
+        slots: Vec<Option<T>>,
+        head: usize,
+        len: usize,
+    }
+
+    impl<T> Deque<T> {
+        pub fn new() -> Self {
+            Self::with_capacity(0)
+        }
+
+        pub fn with_capacity(capacity: usize) -> Self {
+            Self {
+                slots: (0..capacity).map(|_| None).collect(),
+                head: 0,
+                len: 0,
+            }
+        }
+
+        pub fn push_back(&mut self, item: T) {
+            if self.len == self.slots.len() {
+                self.grow();
+            }
+            let index = self.slot(self.len);
+            self.slots[index] = Some(item);
+            self.len += 1;
+        }
+
+        pub fn push_front(&mut self, item: T) {
+            if self.len == self.slots.len() {
+                self.grow();
+            }
+            self.head = (self.head + self.slots.len() - 1) % self.slots.len();
+            self.slots[self.head] = Some(item);
+            self.len += 1;
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            let item = self.slots[self.head].take();
+            self.head = (self.head + 1) % self.slots.len();
+            self.len -= 1;
+            item
+        }
+
+        pub fn pop_back(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            let index = self.slot(self.len - 1);
+            self.len -= 1;
+            self.slots[index].take()
+        }
+
+        pub fn front(&self) -> Option<&T> {
+            self.get(0)
+        }
+
+        pub fn back(&self) -> Option<&T> {
+            self.len.checked_sub(1).and_then(|index| self.get(index))
+        }
+
+        // Counted from the front
+        pub fn get(&self, index: usize) -> Option<&T> {
+            if index >= self.len {
+                return None;
+            }
+            self.slots[self.slot(index)].as_ref()
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.slots.len()
+        }
+
+        pub fn clear(&mut self) {
+            while self.pop_front().is_some() {}
+            self.head = 0;
+        }
+
+        // Front to back
+        pub fn iter(&self) -> Iter<'_, T> {
+            Iter {
+                deque: self,
+                front: 0,
+                back: self.len,
+            }
+        }
+
+        fn slot(&self, index: usize) -> usize {
+            (self.head + index) % self.slots.len()
+        }
+
+        fn grow(&mut self) {
+            let capacity = (self.slots.len() * 2).max(4);
+            let mut slots: Vec<Option<T>> = (0..capacity).map(|_| None).collect();
+            for (index, slot) in slots.iter_mut().enumerate().take(self.len) {
+                let old = self.slot(index);
+                *slot = self.slots[old].take();
+            }
+            self.slots = slots;
+            self.head = 0;
+        }
+    }
+
+    pub struct Iter<'a, T> {
+        deque: &'a Deque<T>,
+        front: usize,
+        back: usize,
+    }
+
+    impl<'a, T> Iterator for Iter<'a, T> {
+        type Item = &'a T;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.front == self.back {
+                return None;
+            }
+            self.front += 1;
+            self.deque.get(self.front - 1)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let remaining = self.back - self.front;
+            (remaining, Some(remaining))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for Iter<'_, T> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            if self.front == self.back {
+                return None;
+            }
+            self.back -= 1;
+            self.deque.get(self.back)
+        }
+    }
+
+    impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+    pub struct IntoIter<T> {
+        deque: Deque<T>,
+    }
+
+    impl<T> Iterator for IntoIter<T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            self.deque.pop_front()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.deque.len, Some(self.deque.len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for IntoIter<T> {
+        fn next_back(&mut self) -> Option<T> {
+            self.deque.pop_back()
+        }
+    }
+
+    impl<T> ExactSizeIterator for IntoIter<T> {}
+
+    impl<T> IntoIterator for Deque<T> {
+        type Item = T;
+        type IntoIter = IntoIter<T>;
+
+        fn into_iter(self) -> IntoIter<T> {
+            IntoIter { deque: self }
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a Deque<T> {
+        type Item = &'a T;
+        type IntoIter = Iter<'a, T>;
+
+        fn into_iter(self) -> Iter<'a, T> {
+            self.iter()
+        }
+    }
+
+    impl<T> Default for Deque<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Extend<T> for Deque<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.push_back(item);
+            }
+        }
+    }
+
+    impl<T> FromIterator<T> for Deque<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let mut deque = Deque::new();
+            deque.extend(iter);
+            deque
+        }
+    }
+
+    // Compares the items in order, regardless of where they sit in the buffer
+    impl<T: PartialEq> PartialEq for Deque<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.len == other.len && self.iter().eq(other.iter())
+        }
+    }
+
+    impl<T: Eq> Eq for Deque<T> {}
+
+    impl<T: fmt::Debug> fmt::Debug for Deque<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    pub fn run() {
+        let mut deque: Deque<i32> = Deque::with_capacity(4);
+        deque.push_back(2);
+        deque.push_back(3);
+        deque.push_front(1);
+        deque.extend(vec![4, 5]);
+        println!("{:?} {}", deque, deque.capacity());
+        println!("{:?} {:?}", deque.pop_front(), deque.pop_back());
+        println!("{:?}", deque.iter().rev().collect::<Vec<&i32>>());
+    }
+}
+
+pub mod queue {
+    use crate::collections::deque::{self, Deque};
+
+    // First in, first out on top of the ring buffer in `Deque`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Queue<T> {
+        items: Deque<T>,
+    }
+
+    impl<T> Queue<T> {
+        pub fn new() -> Self {
+            Self { items: Deque::new() }
+        }
+
+        pub fn with_capacity(capacity: usize) -> Self {
+            Self {
+                items: Deque::with_capacity(capacity),
+            }
+        }
+
+        pub fn enqueue(&mut self, item: T) {
+            self.items.push_back(item);
+        }
+
+        pub fn dequeue(&mut self) -> Option<T> {
+            self.items.pop_front()
+        }
+
+        pub fn peek(&self) -> Option<&T> {
+            self.items.front()
+        }
+
+        pub fn len(&self) -> usize {
+            self.items.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.items.is_empty()
+        }
+
+        // From the front, the order `dequeue` would return the items in
+        pub fn iter(&self) -> deque::Iter<'_, T> {
+            self.items.iter()
+        }
+    }
+
+    impl<T> Default for Queue<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Extend<T> for Queue<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            self.items.extend(iter);
+        }
+    }
+
+    impl<T> FromIterator<T> for Queue<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            Self {
+                items: iter.into_iter().collect(),
+            }
+        }
+    }
+
+    impl<T> IntoIterator for Queue<T> {
+        type Item = T;
+        type IntoIter = deque::IntoIter<T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.items.into_iter()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a Queue<T> {
+        type Item = &'a T;
+        type IntoIter = deque::Iter<'a, T>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    pub fn run() {
+        let mut queue: Queue<&str> = ["a", "b"].into_iter().collect();
+        queue.enqueue("c");
+        println!("{:?}", queue);
+        println!("{:?} {:?} {}", queue.dequeue(), queue.peek(), queue.len());
+    }
+}
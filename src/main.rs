@@ -1,14 +1,22 @@
 use std::collections::BTreeMap;
 
+mod collections;
 mod part1;
 mod part2;
 fn main() {
+    // collections::stack::run();
+    // collections::queue::run();
+    // collections::deque::run();
     // part1::array::run();
     // part1::linkedlist::run();
+    // part1::cache::run();
     // part2::tree::run();
     // part2::avl::run();
     // part2::heaps::run();
     // part2::tries::run();
     // part2::graphs::run();
     part2::ungraphs::run();
+    // part2::flows::run();
+    // part2::assignment::run();
+    // part2::suffix_array::run();
 }
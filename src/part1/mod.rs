@@ -1,4 +1,419 @@
 pub mod array {
+    use std::alloc::{self, Layout};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::mem::{self, MaybeUninit};
+    use std::ops::{Deref, DerefMut};
+    use std::ptr::{self, NonNull};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Overflow {
+        // A full buffer refuses new items and hands them back
+        Reject,
+        // A full buffer drops its oldest item to make room
+        Overwrite,
+    }
+
+    // Fixed-capacity FIFO over an inline array. Items occupy `len` slots starting at
+    // `head` and wrap around the end, only those slots are initialized.
+    pub struct RingBuffer<T, const N: usize> {
+        slots: [MaybeUninit<T>; N],
+        head: usize,
+        len: usize,
+        overflow: Overflow,
+    }
+
+    impl<T, const N: usize> RingBuffer<T, N> {
+        pub fn new(overflow: Overflow) -> Self {
+            Self {
+                slots: [const { MaybeUninit::uninit() }; N],
+                head: 0,
+                len: 0,
+                overflow,
+            }
+        }
+
+        // Ok(None) when there was room, Ok(Some(oldest)) when the oldest item was
+        // overwritten, Err(item) when the buffer is full and rejects
+        pub fn push(&mut self, item: T) -> Result<Option<T>, T> {
+            if !self.is_full() {
+                let index = (self.head + self.len) % N;
+                self.slots[index].write(item);
+                self.len += 1;
+                return Ok(None);
+            }
+            match self.overflow {
+                Overflow::Reject => Err(item),
+                // Nothing to overwrite, the new item is dropped straight away
+                Overflow::Overwrite if N == 0 => Ok(Some(item)),
+                Overflow::Overwrite => {
+                    // The oldest slot becomes the newest one
+                    let oldest = std::mem::replace(&mut self.slots[self.head], MaybeUninit::new(item));
+                    self.head = (self.head + 1) % N;
+                    // SAFETY: the buffer is full, so the head slot was initialized
+                    Ok(Some(unsafe { oldest.assume_init() }))
+                }
+            }
+        }
+
+        // Removes the oldest item
+        pub fn pop(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            // SAFETY: the head slot is initialized while len > 0, and moving head past it
+            // marks it uninitialized again so it is never read twice
+            let item = unsafe { self.slots[self.head].assume_init_read() };
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+            Some(item)
+        }
+
+        pub fn oldest(&self) -> Option<&T> {
+            self.iter().next()
+        }
+
+        pub fn newest(&self) -> Option<&T> {
+            self.iter().next_back()
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn is_full(&self) -> bool {
+            self.len == N
+        }
+
+        pub fn capacity(&self) -> usize {
+            N
+        }
+
+        pub fn clear(&mut self) {
+            while self.pop().is_some() {}
+        }
+
+        // The items from oldest to newest, split where they wrap around the array
+        pub fn as_slices(&self) -> (&[T], &[T]) {
+            let first = self.len.min(N - self.head);
+            let second = self.len - first;
+            // SAFETY: both ranges lie inside the `len` initialized slots starting at `head`,
+            // and MaybeUninit<T> has the same layout as T
+            unsafe {
+                let slots = self.slots.as_ptr() as *const T;
+                (
+                    std::slice::from_raw_parts(slots.add(self.head), first),
+                    std::slice::from_raw_parts(slots, second),
+                )
+            }
+        }
+
+        pub fn iter(&self) -> std::iter::Chain<std::slice::Iter<'_, T>, std::slice::Iter<'_, T>> {
+            let (first, second) = self.as_slices();
+            first.iter().chain(second.iter())
+        }
+    }
+
+    impl<T, const N: usize> Drop for RingBuffer<T, N> {
+        fn drop(&mut self) {
+            self.clear();
+        }
+    }
+
+    impl<T: Clone, const N: usize> Clone for RingBuffer<T, N> {
+        fn clone(&self) -> Self {
+            let mut buffer = Self::new(self.overflow);
+            for item in self.iter() {
+                let _ = buffer.push(item.clone());
+            }
+            buffer
+        }
+    }
+
+    impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<'a, T, const N: usize> IntoIterator for &'a RingBuffer<T, N> {
+        type Item = &'a T;
+        type IntoIter = std::iter::Chain<std::slice::Iter<'a, T>, std::slice::Iter<'a, T>>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.iter()
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ShrinkPolicy {
+        Never,
+        // Halve the capacity once at most a quarter of it is in use, the gap between the
+        // two thresholds keeps alternating push/pop from reallocating every time
+        Quarter,
+    }
+
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    pub struct ArrayStats {
+        pub grows: usize,
+        pub shrinks: usize,
+        // Items copied into a new allocation, the amortized cost per push is moved / pushes
+        pub moved: usize,
+        pub pushes: usize,
+    }
+
+    impl ArrayStats {
+        pub fn reallocations(&self) -> usize {
+            self.grows + self.shrinks
+        }
+    }
+
+    // Growable array on a raw allocation, like Vec but with the growth strategy exposed
+    // and every reallocation counted
+    pub struct DynArray<T> {
+        ptr: NonNull<T>,
+        capacity: usize,
+        len: usize,
+        growth_factor: f64,
+        shrink_policy: ShrinkPolicy,
+        stats: ArrayStats,
+        marker: PhantomData<T>,
+    }
+
+    impl<T> DynArray<T> {
+        pub fn new() -> Self {
+            Self {
+                ptr: NonNull::dangling(),
+                // Zero-sized items never need memory
+                capacity: if mem::size_of::<T>() == 0 { usize::MAX } else { 0 },
+                len: 0,
+                growth_factor: 2.0,
+                shrink_policy: ShrinkPolicy::Never,
+                stats: ArrayStats::default(),
+                marker: PhantomData,
+            }
+        }
+
+        pub fn with_capacity(capacity: usize) -> Self {
+            let mut array = Self::new();
+            if capacity > array.capacity {
+                array.reallocate(capacity);
+            }
+            array
+        }
+
+        pub fn growth_factor(mut self, growth_factor: f64) -> Self {
+            assert!(growth_factor > 1.0, "the growth factor must be above 1");
+            self.growth_factor = growth_factor;
+            self
+        }
+
+        pub fn shrink_policy(mut self, shrink_policy: ShrinkPolicy) -> Self {
+            self.shrink_policy = shrink_policy;
+            self
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.capacity
+        }
+
+        pub fn stats(&self) -> ArrayStats {
+            self.stats
+        }
+
+        pub fn push(&mut self, item: T) {
+            self.stats.pushes += 1;
+            if self.len == self.capacity {
+                self.grow();
+            }
+            // SAFETY: len < capacity, so the slot is inside the allocation and unused
+            unsafe { self.ptr.as_ptr().add(self.len).write(item) };
+            self.len += 1;
+        }
+
+        pub fn pop(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            self.len -= 1;
+            // SAFETY: the slot held an item, and lowering len hands its ownership to us
+            let item = unsafe { self.ptr.as_ptr().add(self.len).read() };
+            self.shrink();
+            Some(item)
+        }
+
+        // Shifts the items from the index onwards one place to the right
+        pub fn insert(&mut self, index: usize, item: T) {
+            assert!(index <= self.len, "insertion index {} is out of bounds for length {}", index, self.len);
+            self.stats.pushes += 1;
+            if self.len == self.capacity {
+                self.grow();
+            }
+            // SAFETY: len < capacity, so the shifted range still fits the allocation
+            unsafe {
+                let slot = self.ptr.as_ptr().add(index);
+                ptr::copy(slot, slot.add(1), self.len - index);
+                slot.write(item);
+            }
+            self.len += 1;
+        }
+
+        // Shifts the items after the index one place to the left
+        pub fn remove(&mut self, index: usize) -> T {
+            assert!(index < self.len, "removal index {} is out of bounds for length {}", index, self.len);
+            // SAFETY: the index holds an item, which is read out before the tail moves over it
+            let item = unsafe {
+                let slot = self.ptr.as_ptr().add(index);
+                let item = slot.read();
+                ptr::copy(slot.add(1), slot, self.len - index - 1);
+                item
+            };
+            self.len -= 1;
+            self.shrink();
+            item
+        }
+
+        pub fn clear(&mut self) {
+            while self.pop().is_some() {}
+        }
+
+        pub fn as_slice(&self) -> &[T] {
+            // SAFETY: the first len slots hold items, the pointer is dangling but aligned
+            // when nothing is allocated
+            unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+        }
+
+        pub fn as_mut_slice(&mut self) -> &mut [T] {
+            // SAFETY: as in `as_slice`
+            unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+        }
+
+        fn grow(&mut self) {
+            let scaled = (self.capacity as f64 * self.growth_factor).ceil() as usize;
+            self.stats.grows += 1;
+            self.reallocate(scaled.max(self.capacity + 1));
+        }
+
+        fn shrink(&mut self) {
+            if self.shrink_policy == ShrinkPolicy::Quarter
+                && mem::size_of::<T>() != 0
+                && self.capacity > 0
+                && self.len <= self.capacity / 4
+            {
+                self.stats.shrinks += 1;
+                self.reallocate(self.capacity / 2);
+            }
+        }
+
+        // Moves the items to an allocation of the new capacity, which must fit them
+        fn reallocate(&mut self, capacity: usize) {
+            let new_layout = Layout::array::<T>(capacity).expect("capacity overflow");
+            let ptr = if self.capacity == 0 {
+                // SAFETY: the size is non-zero, T is not zero-sized and capacity > len >= 0
+                unsafe { alloc::alloc(new_layout) }
+            } else {
+                let old_layout = Layout::array::<T>(self.capacity).unwrap();
+                if capacity == 0 {
+                    // SAFETY: ptr was allocated with old_layout and holds no items (len <= capacity)
+                    unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, old_layout) };
+                    self.ptr = NonNull::dangling();
+                    self.capacity = 0;
+                    return;
+                }
+                // SAFETY: ptr was allocated with old_layout, the new size is non-zero
+                unsafe { alloc::realloc(self.ptr.as_ptr() as *mut u8, old_layout, new_layout.size()) }
+            };
+            self.ptr = match NonNull::new(ptr as *mut T) {
+                Some(ptr) => ptr,
+                None => alloc::handle_alloc_error(new_layout),
+            };
+            self.stats.moved += self.len;
+            self.capacity = capacity;
+        }
+    }
+
+    impl<T> Drop for DynArray<T> {
+        fn drop(&mut self) {
+            // SAFETY: the first len slots hold items, dropped exactly once here
+            unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+            if mem::size_of::<T>() != 0 && self.capacity > 0 {
+                // SAFETY: ptr was allocated with this layout
+                unsafe { alloc::dealloc(self.ptr.as_ptr() as *mut u8, Layout::array::<T>(self.capacity).unwrap()) };
+            }
+        }
+    }
+
+    impl<T> Default for DynArray<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Deref for DynArray<T> {
+        type Target = [T];
+
+        fn deref(&self) -> &[T] {
+            self.as_slice()
+        }
+    }
+
+    impl<T> DerefMut for DynArray<T> {
+        fn deref_mut(&mut self) -> &mut [T] {
+            self.as_mut_slice()
+        }
+    }
+
+    impl<T> Extend<T> for DynArray<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.push(item);
+            }
+        }
+    }
+
+    impl<T> FromIterator<T> for DynArray<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let mut array = DynArray::new();
+            array.extend(iter);
+            array
+        }
+    }
+
+    // Keeps the growth settings, the copy starts with fresh statistics
+    impl<T: Clone> Clone for DynArray<T> {
+        fn clone(&self) -> Self {
+            let mut array = DynArray::with_capacity(self.len)
+                .growth_factor(self.growth_factor)
+                .shrink_policy(self.shrink_policy);
+            array.extend(self.iter().cloned());
+            array.stats = ArrayStats::default();
+            array
+        }
+    }
+
+    impl<T: PartialEq> PartialEq for DynArray<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.as_slice() == other.as_slice()
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for DynArray<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
 
     pub fn run() {
         let mut a: Vec<u16> = vec![0; 3];
@@ -6,17 +421,1250 @@ pub mod array {
         a[1] = 2;
         a[2] = 3;
         println!("{:?}", a);
+
+        let mut samples: RingBuffer<u16, 4> = RingBuffer::new(Overflow::Overwrite);
+        for sample in 1..=6 {
+            println!("{:?}", samples.push(sample));
+        }
+        println!("{:?} {:?}", samples, samples.as_slices());
+        println!("{:?} {:?}", samples.oldest(), samples.newest());
+
+        let mut pending: RingBuffer<u16, 2> = RingBuffer::new(Overflow::Reject);
+        println!("{:?} {:?} {:?}", pending.push(1), pending.push(2), pending.push(3));
+        println!("{:?} {:?}", pending.pop(), pending.push(3));
+
+        // Amortized cost of pushing: items copied by reallocations per push
+        for growth_factor in [2.0, 1.5, 1.1] {
+            let mut array = DynArray::new().growth_factor(growth_factor);
+            array.extend(0..10_000);
+            let stats = array.stats();
+            println!(
+                "factor {}: {} reallocations, {:.2} moves per push, capacity {}",
+                growth_factor,
+                stats.reallocations(),
+                stats.moved as f64 / stats.pushes as f64,
+                array.capacity()
+            );
+        }
+
+        let mut vec: Vec<i32> = Vec::new();
+        let mut vec_reallocations = 0;
+        for i in 0..10_000 {
+            let capacity = vec.capacity();
+            vec.push(i);
+            if vec.capacity() != capacity {
+                vec_reallocations += 1;
+            }
+        }
+        println!("Vec: {} reallocations, capacity {}", vec_reallocations, vec.capacity());
+
+        let mut array: DynArray<i32> = DynArray::new().shrink_policy(ShrinkPolicy::Quarter);
+        array.extend(0..8);
+        array.insert(0, -1);
+        array.remove(3);
+        while array.len() > 2 {
+            array.pop();
+        }
+        println!("{:?} {} {:?}", array, array.capacity(), array.stats());
     }
 }
 
 pub mod linkedlist {
-    use std::collections::{HashSet, LinkedList};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::ptr::NonNull;
+
+    struct SinglyNode<T> {
+        item: T,
+        next: Option<Box<SinglyNode<T>>>,
+    }
+
+    pub struct SinglyLinkedList<T> {
+        head: Option<Box<SinglyNode<T>>>,
+        len: usize,
+    }
+
+    impl<T> SinglyLinkedList<T> {
+        pub fn new() -> Self {
+            Self { head: None, len: 0 }
+        }
+
+        pub fn push_front(&mut self, item: T) {
+            let next = self.head.take();
+            self.head = Some(Box::new(SinglyNode { item, next }));
+            self.len += 1;
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            let node = self.head.take()?;
+            self.head = node.next;
+            self.len -= 1;
+            Some(node.item)
+        }
+
+        pub fn front(&self) -> Option<&T> {
+            self.head.as_ref().map(|node| &node.item)
+        }
+
+        pub fn front_mut(&mut self) -> Option<&mut T> {
+            self.head.as_mut().map(|node| &mut node.item)
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.head.is_none()
+        }
+
+        pub fn iter(&self) -> SinglyIter<'_, T> {
+            SinglyIter {
+                next: self.head.as_deref(),
+            }
+        }
+
+        // Turns every link around in a single pass
+        pub fn reverse(&mut self) {
+            let mut reversed = None;
+            let mut current = self.head.take();
+            while let Some(mut node) = current {
+                current = node.next.take();
+                node.next = reversed;
+                reversed = Some(node);
+            }
+            self.head = reversed;
+        }
+
+        // The hare moves two links for every one of the tortoise, so the tortoise is halfway
+        // when the hare runs out. Even lengths give the first of the two middle items.
+        pub fn middle(&self) -> Option<&T> {
+            let mut slow = self.head.as_deref()?;
+            let mut fast = slow;
+            while let Some(next) = fast.next.as_deref().and_then(|next| next.next.as_deref()) {
+                fast = next;
+                slow = slow.next.as_deref().unwrap();
+            }
+            Some(&slow.item)
+        }
+    }
+
+    impl<T> Default for SinglyLinkedList<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    // Dropping the head recursively would use one stack frame per node
+    impl<T> Drop for SinglyLinkedList<T> {
+        fn drop(&mut self) {
+            let mut current = self.head.take();
+            while let Some(mut node) = current {
+                current = node.next.take();
+            }
+        }
+    }
+
+    // Keeps the order of the iterator, the first item becomes the front
+    impl<T> FromIterator<T> for SinglyLinkedList<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let mut list = SinglyLinkedList::new();
+            for item in iter {
+                list.push_front(item);
+            }
+            list.reverse();
+            list
+        }
+    }
+
+    impl<T: Clone> Clone for SinglyLinkedList<T> {
+        fn clone(&self) -> Self {
+            self.iter().cloned().collect()
+        }
+    }
+
+    impl<T: PartialEq> PartialEq for SinglyLinkedList<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.len == other.len && self.iter().eq(other.iter())
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for SinglyLinkedList<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    pub struct SinglyIter<'a, T> {
+        next: Option<&'a SinglyNode<T>>,
+    }
+
+    impl<'a, T> Iterator for SinglyIter<'a, T> {
+        type Item = &'a T;
+
+        fn next(&mut self) -> Option<&'a T> {
+            let node = self.next?;
+            self.next = node.next.as_deref();
+            Some(&node.item)
+        }
+    }
+
+    pub struct SinglyIntoIter<T> {
+        list: SinglyLinkedList<T>,
+    }
+
+    impl<T> Iterator for SinglyIntoIter<T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            self.list.pop_front()
+        }
+    }
+
+    impl<T> IntoIterator for SinglyLinkedList<T> {
+        type Item = T;
+        type IntoIter = SinglyIntoIter<T>;
+
+        fn into_iter(self) -> SinglyIntoIter<T> {
+            SinglyIntoIter { list: self }
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a SinglyLinkedList<T> {
+        type Item = &'a T;
+        type IntoIter = SinglyIter<'a, T>;
+
+        fn into_iter(self) -> SinglyIter<'a, T> {
+            self.iter()
+        }
+    }
+
+    type Link<T> = Option<NonNull<Node<T>>>;
+
+    struct Node<T> {
+        item: T,
+        prev: Link<T>,
+        next: Link<T>,
+    }
+
+    // Every node is a leaked Box owned by the list; `prev`/`next` are raw pointers
+    // between them, and a node is turned back into a Box exactly once, when unlinked
+    pub struct DoublyLinkedList<T> {
+        head: Link<T>,
+        tail: Link<T>,
+        len: usize,
+        marker: PhantomData<Box<Node<T>>>,
+    }
+
+    impl<T> DoublyLinkedList<T> {
+        pub fn new() -> Self {
+            Self {
+                head: None,
+                tail: None,
+                len: 0,
+                marker: PhantomData,
+            }
+        }
+
+        // Links a new node between two neighbouring nodes, None standing for the list ends
+        fn link(&mut self, item: T, prev: Link<T>, next: Link<T>) -> NonNull<Node<T>> {
+            let node = NonNull::from(Box::leak(Box::new(Node { item, prev: None, next: None })));
+            // SAFETY: the node is new, prev and next are nodes of this list or None
+            unsafe { self.attach(node, prev, next) };
+            node
+        }
+
+        // SAFETY: the node must not be in any list, prev and next must be neighbouring
+        // nodes of this list or None
+        unsafe fn attach(&mut self, node: NonNull<Node<T>>, prev: Link<T>, next: Link<T>) {
+            (*node.as_ptr()).prev = prev;
+            (*node.as_ptr()).next = next;
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = Some(node),
+                None => self.head = Some(node),
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = Some(node),
+                None => self.tail = Some(node),
+            }
+            self.len += 1;
+        }
+
+        // Takes the node out of the chain without freeing it
+        // SAFETY: the node must belong to this list
+        unsafe fn detach(&mut self, node: NonNull<Node<T>>) {
+            let (prev, next) = ((*node.as_ptr()).prev, (*node.as_ptr()).next);
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = next,
+                None => self.head = next,
+            }
+            match next {
+                Some(next) => (*next.as_ptr()).prev = prev,
+                None => self.tail = prev,
+            }
+            self.len -= 1;
+        }
+
+        // SAFETY: the node must belong to this list
+        unsafe fn unlink(&mut self, node: NonNull<Node<T>>) -> T {
+            self.detach(node);
+            Box::from_raw(node.as_ptr()).item
+        }
+
+        // Moves every node of the other list between two neighbouring nodes of this one
+        fn splice_between(&mut self, prev: Link<T>, next: Link<T>, mut other: Self) {
+            let (first, last) = match (other.head.take(), other.tail.take()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => return,
+            };
+            // SAFETY: first and last now belong to this list only, prev and next are nodes
+            // of this list or None
+            unsafe {
+                (*first.as_ptr()).prev = prev;
+                (*last.as_ptr()).next = next;
+                match prev {
+                    Some(prev) => (*prev.as_ptr()).next = Some(first),
+                    None => self.head = Some(first),
+                }
+                match next {
+                    Some(next) => (*next.as_ptr()).prev = Some(last),
+                    None => self.tail = Some(last),
+                }
+            }
+            self.len += other.len;
+            other.len = 0;
+        }
+
+        pub fn push_front(&mut self, item: T) {
+            self.link(item, None, self.head);
+        }
+
+        pub fn push_back(&mut self, item: T) {
+            self.link(item, self.tail, None);
+        }
+
+        // Like `push_back`, keeping a handle to reach the item again in O(1)
+        pub fn push_back_handle(&mut self, item: T) -> Handle<T> {
+            Handle {
+                node: self.link(item, self.tail, None),
+            }
+        }
+
+        pub fn push_front_handle(&mut self, item: T) -> Handle<T> {
+            Handle {
+                node: self.link(item, None, self.head),
+            }
+        }
+
+        pub fn front_handle(&self) -> Option<Handle<T>> {
+            self.head.map(|node| Handle { node })
+        }
+
+        /// Returns the item the handle refers to.
+        ///
+        /// # Safety
+        ///
+        /// `handle` must come from this list, and its item must not have been removed since.
+        pub unsafe fn get(&self, handle: Handle<T>) -> &T {
+            &(*handle.node.as_ptr()).item
+        }
+
+        /// Returns the item the handle refers to, mutably.
+        ///
+        /// # Safety
+        ///
+        /// `handle` must come from this list, and its item must not have been removed since.
+        pub unsafe fn get_mut(&mut self, handle: Handle<T>) -> &mut T {
+            &mut (*handle.node.as_ptr()).item
+        }
+
+        /// Unlinks the item and returns it. The handle is dangling afterwards and must not be
+        /// used again.
+        ///
+        /// # Safety
+        ///
+        /// `handle` must come from this list, and its item must not have been removed since.
+        pub unsafe fn remove(&mut self, handle: Handle<T>) -> T {
+            self.unlink(handle.node)
+        }
+
+        /// Moves the item to the back of the list. The handle stays valid.
+        ///
+        /// # Safety
+        ///
+        /// `handle` must come from this list, and its item must not have been removed since.
+        pub unsafe fn move_to_back(&mut self, handle: Handle<T>) {
+            self.detach(handle.node);
+            self.attach(handle.node, self.tail, None);
+        }
+
+        /// Handle of the item after the handle's one, None when it is at the back.
+        ///
+        /// # Safety
+        ///
+        /// `handle` must come from this list, and its item must not have been removed since.
+        pub unsafe fn next_handle(&self, handle: Handle<T>) -> Option<Handle<T>> {
+            (*handle.node.as_ptr()).next.map(|node| Handle { node })
+        }
+
+        /// Links a new item right after the handle's one and returns the new item's handle.
+        ///
+        /// # Safety
+        ///
+        /// `handle` must come from this list, and its item must not have been removed since.
+        pub unsafe fn insert_after_handle(&mut self, handle: Handle<T>, item: T) -> Handle<T> {
+            let next = (*handle.node.as_ptr()).next;
+            Handle {
+                node: self.link(item, Some(handle.node), next),
+            }
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            // SAFETY: the head is a node of this list
+            self.head.map(|head| unsafe { self.unlink(head) })
+        }
+
+        pub fn pop_back(&mut self) -> Option<T> {
+            // SAFETY: the tail is a node of this list
+            self.tail.map(|tail| unsafe { self.unlink(tail) })
+        }
+
+        pub fn front(&self) -> Option<&T> {
+            // SAFETY: nodes live as long as the list and are only changed through &mut self
+            self.head.map(|head| unsafe { &(*head.as_ptr()).item })
+        }
+
+        pub fn back(&self) -> Option<&T> {
+            // SAFETY: as in `front`
+            self.tail.map(|tail| unsafe { &(*tail.as_ptr()).item })
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        pub fn clear(&mut self) {
+            while self.pop_front().is_some() {}
+        }
+
+        pub fn iter(&self) -> Iter<'_, T> {
+            Iter {
+                head: self.head,
+                tail: self.tail,
+                len: self.len,
+                marker: PhantomData,
+            }
+        }
+
+        pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+            IterMut {
+                head: self.head,
+                tail: self.tail,
+                len: self.len,
+                marker: PhantomData,
+            }
+        }
+
+        // Swaps the links of every node, then the ends of the list
+        pub fn reverse(&mut self) {
+            let mut current = self.head;
+            while let Some(node) = current {
+                // SAFETY: node belongs to this list and nothing else refers to it
+                unsafe {
+                    let node = &mut *node.as_ptr();
+                    std::mem::swap(&mut node.prev, &mut node.next);
+                    current = node.prev;
+                }
+            }
+            std::mem::swap(&mut self.head, &mut self.tail);
+        }
+
+        // Same tortoise and hare walk as `SinglyLinkedList::middle`
+        pub fn middle(&self) -> Option<&T> {
+            let mut slow = self.head?;
+            let mut fast = slow;
+            // SAFETY: every pointer followed is a node of this list
+            unsafe {
+                while let Some(next) = (*fast.as_ptr()).next.and_then(|next| (*next.as_ptr()).next) {
+                    fast = next;
+                    slow = (*slow.as_ptr()).next.unwrap();
+                }
+                Some(&(*slow.as_ptr()).item)
+            }
+        }
+
+        // Cursor on the first item, or on the ghost position of an empty list
+        pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+            CursorMut {
+                current: self.head,
+                index: 0,
+                list: self,
+            }
+        }
+
+        pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+            CursorMut {
+                current: self.tail,
+                index: self.len.saturating_sub(1),
+                list: self,
+            }
+        }
+    }
+
+    impl<T> Default for DoublyLinkedList<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> Drop for DoublyLinkedList<T> {
+        fn drop(&mut self) {
+            self.clear();
+        }
+    }
+
+    impl<T> Extend<T> for DoublyLinkedList<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.push_back(item);
+            }
+        }
+    }
+
+    impl<T> FromIterator<T> for DoublyLinkedList<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let mut list = DoublyLinkedList::new();
+            list.extend(iter);
+            list
+        }
+    }
+
+    impl<T: Clone> Clone for DoublyLinkedList<T> {
+        fn clone(&self) -> Self {
+            self.iter().cloned().collect()
+        }
+    }
+
+    impl<T: PartialEq> PartialEq for DoublyLinkedList<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.len == other.len && self.iter().eq(other.iter())
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for DoublyLinkedList<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    pub struct Iter<'a, T> {
+        head: Link<T>,
+        tail: Link<T>,
+        len: usize,
+        marker: PhantomData<&'a Node<T>>,
+    }
+
+    impl<'a, T> Iterator for Iter<'a, T> {
+        type Item = &'a T;
+
+        fn next(&mut self) -> Option<&'a T> {
+            if self.len == 0 {
+                return None;
+            }
+            // SAFETY: len counts the nodes left between head and tail, and the list is
+            // borrowed for 'a
+            self.head.map(|node| unsafe {
+                self.len -= 1;
+                self.head = (*node.as_ptr()).next;
+                &(*node.as_ptr()).item
+            })
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.len, Some(self.len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for Iter<'_, T> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            if self.len == 0 {
+                return None;
+            }
+            // SAFETY: as in `next`
+            self.tail.map(|node| unsafe {
+                self.len -= 1;
+                self.tail = (*node.as_ptr()).prev;
+                &(*node.as_ptr()).item
+            })
+        }
+    }
+
+    impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+    pub struct IterMut<'a, T> {
+        head: Link<T>,
+        tail: Link<T>,
+        len: usize,
+        marker: PhantomData<&'a mut Node<T>>,
+    }
+
+    impl<'a, T> Iterator for IterMut<'a, T> {
+        type Item = &'a mut T;
+
+        fn next(&mut self) -> Option<&'a mut T> {
+            if self.len == 0 {
+                return None;
+            }
+            // SAFETY: as in `Iter::next`, each node is handed out at most once
+            self.head.map(|node| unsafe {
+                self.len -= 1;
+                self.head = (*node.as_ptr()).next;
+                &mut (*node.as_ptr()).item
+            })
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.len, Some(self.len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for IterMut<'_, T> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            if self.len == 0 {
+                return None;
+            }
+            // SAFETY: as in `next`
+            self.tail.map(|node| unsafe {
+                self.len -= 1;
+                self.tail = (*node.as_ptr()).prev;
+                &mut (*node.as_ptr()).item
+            })
+        }
+    }
+
+    impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+    pub struct IntoIter<T> {
+        list: DoublyLinkedList<T>,
+    }
+
+    impl<T> Iterator for IntoIter<T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            self.list.pop_front()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.list.len, Some(self.list.len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for IntoIter<T> {
+        fn next_back(&mut self) -> Option<T> {
+            self.list.pop_back()
+        }
+    }
+
+    impl<T> ExactSizeIterator for IntoIter<T> {}
+
+    impl<T> IntoIterator for DoublyLinkedList<T> {
+        type Item = T;
+        type IntoIter = IntoIter<T>;
+
+        fn into_iter(self) -> IntoIter<T> {
+            IntoIter { list: self }
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a DoublyLinkedList<T> {
+        type Item = &'a T;
+        type IntoIter = Iter<'a, T>;
+
+        fn into_iter(self) -> Iter<'a, T> {
+            self.iter()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a mut DoublyLinkedList<T> {
+        type Item = &'a mut T;
+        type IntoIter = IterMut<'a, T>;
+
+        fn into_iter(self) -> IterMut<'a, T> {
+            self.iter_mut()
+        }
+    }
+
+    // Refers to one item of a `DoublyLinkedList`, for callers that keep their own index
+    // of the nodes (e.g. caches). Only valid while the item is in the list.
+    pub struct Handle<T> {
+        node: NonNull<Node<T>>,
+    }
+
+    impl<T> Clone for Handle<T> {
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+
+    impl<T> Copy for Handle<T> {}
+
+    impl<T> fmt::Debug for Handle<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("Handle").field(&self.node).finish()
+        }
+    }
+
+    // Points at an item of the list or at the "ghost" position between the back and the
+    // front, where `current` is None. Moving past either end lands on the ghost, and
+    // moving again wraps around to the other end.
+    pub struct CursorMut<'a, T> {
+        current: Link<T>,
+        // Position of the current item, equal to the length on the ghost
+        index: usize,
+        list: &'a mut DoublyLinkedList<T>,
+    }
+
+    impl<'a, T> CursorMut<'a, T> {
+        pub fn index(&self) -> Option<usize> {
+            self.current.map(|_| self.index)
+        }
+
+        pub fn current(&mut self) -> Option<&mut T> {
+            // SAFETY: the node belongs to the list, which the cursor borrows mutably
+            self.current.map(|node| unsafe { &mut (*node.as_ptr()).item })
+        }
+
+        pub fn move_next(&mut self) {
+            match self.current {
+                // SAFETY: the node belongs to the list
+                Some(node) => unsafe {
+                    self.current = (*node.as_ptr()).next;
+                    self.index += 1;
+                },
+                None => {
+                    self.current = self.list.head;
+                    self.index = 0;
+                }
+            }
+        }
+
+        pub fn move_prev(&mut self) {
+            match self.current {
+                // SAFETY: the node belongs to the list
+                Some(node) => unsafe {
+                    self.current = (*node.as_ptr()).prev;
+                    self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+                },
+                None => {
+                    self.current = self.list.tail;
+                    self.index = self.list.len.saturating_sub(1);
+                }
+            }
+        }
+
+        fn next_link(&self) -> Link<T> {
+            match self.current {
+                // SAFETY: the node belongs to the list
+                Some(node) => unsafe { (*node.as_ptr()).next },
+                None => self.list.head,
+            }
+        }
+
+        fn prev_link(&self) -> Link<T> {
+            match self.current {
+                // SAFETY: the node belongs to the list
+                Some(node) => unsafe { (*node.as_ptr()).prev },
+                None => self.list.tail,
+            }
+        }
+
+        // On the ghost the item goes to the front of the list
+        pub fn insert_after(&mut self, item: T) {
+            let next = self.next_link();
+            self.list.link(item, self.current, next);
+            if self.current.is_none() {
+                self.index += 1;
+            }
+        }
+
+        // On the ghost the item goes to the back of the list
+        pub fn insert_before(&mut self, item: T) {
+            let prev = self.prev_link();
+            self.list.link(item, prev, self.current);
+            self.index += 1;
+        }
+
+        // The cursor moves on to the next item, or the ghost after the last one
+        pub fn remove_current(&mut self) -> Option<T> {
+            let node = self.current?;
+            self.current = self.next_link();
+            // SAFETY: the node belongs to the list
+            Some(unsafe { self.list.unlink(node) })
+        }
+
+        // Everything after the cursor as a new list, the whole list when on the ghost
+        pub fn split_after(&mut self) -> DoublyLinkedList<T> {
+            let node = match self.current {
+                Some(node) => node,
+                None => {
+                    self.index = 0;
+                    return std::mem::take(self.list);
+                }
+            };
+            let mut rest = DoublyLinkedList::new();
+            // SAFETY: node and its successors belong to the list; the successors are moved
+            // to `rest` and the two lists are cut apart on both sides
+            unsafe {
+                if let Some(next) = (*node.as_ptr()).next.take() {
+                    (*next.as_ptr()).prev = None;
+                    rest.head = Some(next);
+                    rest.tail = self.list.tail;
+                    rest.len = self.list.len - self.index - 1;
+                    self.list.tail = Some(node);
+                    self.list.len = self.index + 1;
+                }
+            }
+            rest
+        }
+
+        // On the ghost the items go to the front of the list
+        pub fn splice_after(&mut self, other: DoublyLinkedList<T>) {
+            let added = other.len;
+            let next = self.next_link();
+            self.list.splice_between(self.current, next, other);
+            if self.current.is_none() {
+                self.index += added;
+            }
+        }
+
+        // On the ghost the items go to the back of the list
+        pub fn splice_before(&mut self, other: DoublyLinkedList<T>) {
+            let added = other.len;
+            let prev = self.prev_link();
+            self.list.splice_between(prev, self.current, other);
+            self.index += added;
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Cycle {
+        // Steps from the start to the first node on the cycle
+        pub start: usize,
+        pub length: usize,
+    }
+
+    // Floyd's tortoise and hare over any chain of links, e.g. `next` indices of a list kept
+    // in a Vec, where None ends the chain. Constant memory, linear in the chain length.
+    pub fn find_cycle<N, F>(start: N, next: F) -> Option<Cycle>
+    where
+        N: PartialEq + Clone,
+        F: Fn(&N) -> Option<N>,
+    {
+        let mut tortoise = next(&start)?;
+        let mut hare = next(&tortoise)?;
+        while tortoise != hare {
+            tortoise = next(&tortoise)?;
+            hare = next(&next(&hare)?)?;
+        }
+
+        // The meeting point is as far from the cycle start as the start is from the chain start
+        let mut start_steps = 0;
+        tortoise = start;
+        while tortoise != hare {
+            tortoise = next(&tortoise).unwrap();
+            hare = next(&hare).unwrap();
+            start_steps += 1;
+        }
+
+        let mut length = 1;
+        hare = next(&tortoise).unwrap();
+        while tortoise != hare {
+            hare = next(&hare).unwrap();
+            length += 1;
+        }
+        Some(Cycle {
+            start: start_steps,
+            length,
+        })
+    }
 
     pub fn run() {
-        let mut list: LinkedList<i16> = LinkedList::new();
+        let mut list: DoublyLinkedList<i16> = DoublyLinkedList::new();
         list.push_back(1);
         list.push_back(2);
         list.push_front(6);
         println!("{:?}", list);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(5);
+        cursor.insert_after(3);
+        println!("{:?} {:?}", cursor.index(), cursor.current());
+        let tail = cursor.split_after();
+        cursor.splice_before((7..9).collect());
+        println!("{:?} {:?}", list, tail);
+        list.reverse();
+        println!("{:?} {:?}", list, list.middle());
+
+        let mut singly: SinglyLinkedList<i16> = (1..=5).collect();
+        singly.reverse();
+        println!("{:?} {:?}", singly, singly.middle());
+
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 2
+        let next = [Some(1), Some(2), Some(3), Some(4), Some(2)];
+        println!("{:?}", find_cycle(0, |&node: &usize| next[node]));
+        println!("{:?}", find_cycle(0, |&node: &usize| (node < 4).then_some(node + 1)));
+    }
+}
+
+pub mod cache {
+    use crate::part1::linkedlist::{DoublyLinkedList, Handle};
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::hash::Hash;
+
+    type EvictionCallback<K, V> = Box<dyn FnMut(K, V)>;
+
+    // Recency order lives in the list, least recently used at the front, and the map
+    // holds a handle to every entry's node so lookups can move it to the back in O(1)
+    pub struct LruCache<K, V> {
+        entries: DoublyLinkedList<(K, V)>,
+        handles: HashMap<K, Handle<(K, V)>>,
+        capacity: usize,
+        on_evict: Option<EvictionCallback<K, V>>,
+    }
+
+    impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                entries: DoublyLinkedList::new(),
+                handles: HashMap::with_capacity(capacity),
+                capacity,
+                on_evict: None,
+            }
+        }
+
+        // Called with every entry dropped to make room, not with ones removed explicitly
+        pub fn on_evict(mut self, callback: impl FnMut(K, V) + 'static) -> Self {
+            self.on_evict = Some(Box::new(callback));
+            self
+        }
+
+        pub fn get(&mut self, key: &K) -> Option<&V> {
+            let handle = *self.handles.get(key)?;
+            // SAFETY: handles in the map always point at nodes still in `entries`
+            unsafe {
+                self.entries.move_to_back(handle);
+                Some(&self.entries.get(handle).1)
+            }
+        }
+
+        pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+            let handle = *self.handles.get(key)?;
+            // SAFETY: as in `get`
+            unsafe {
+                self.entries.move_to_back(handle);
+                Some(&mut self.entries.get_mut(handle).1)
+            }
+        }
+
+        // Looks the value up without making it more recent
+        pub fn peek(&self, key: &K) -> Option<&V> {
+            let handle = *self.handles.get(key)?;
+            // SAFETY: as in `get`
+            unsafe { Some(&self.entries.get(handle).1) }
+        }
+
+        pub fn contains(&self, key: &K) -> bool {
+            self.handles.contains_key(key)
+        }
+
+        // Returns the previous value when the key was already cached. A cache with no
+        // capacity stores nothing, the entry is dropped without reaching `on_evict`.
+        pub fn put(&mut self, key: K, value: V) -> Option<V> {
+            if let Some(&handle) = self.handles.get(&key) {
+                // SAFETY: as in `get`
+                unsafe {
+                    self.entries.move_to_back(handle);
+                    return Some(std::mem::replace(&mut self.entries.get_mut(handle).1, value));
+                }
+            }
+            if self.capacity == 0 {
+                return None;
+            }
+
+            let handle = self.entries.push_back_handle((key.clone(), value));
+            self.handles.insert(key, handle);
+            self.evict();
+            None
+        }
+
+        pub fn remove(&mut self, key: &K) -> Option<V> {
+            let handle = self.handles.remove(key)?;
+            // SAFETY: the handle was in the map, and is gone from it with its node
+            unsafe { Some(self.entries.remove(handle).1) }
+        }
+
+        pub fn pop_lru(&mut self) -> Option<(K, V)> {
+            let (key, value) = self.entries.pop_front()?;
+            self.handles.remove(&key);
+            Some((key, value))
+        }
+
+        pub fn peek_lru(&self) -> Option<(&K, &V)> {
+            self.entries.front().map(|(key, value)| (key, value))
+        }
+
+        pub fn resize(&mut self, capacity: usize) {
+            self.capacity = capacity;
+            self.evict();
+        }
+
+        fn evict(&mut self) {
+            while self.entries.len() > self.capacity {
+                let (key, value) = self.pop_lru().unwrap();
+                if let Some(callback) = self.on_evict.as_mut() {
+                    callback(key, value);
+                }
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.capacity
+        }
+
+        // Least recently used first
+        pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+            self.entries.iter().map(|(key, value)| (key, value))
+        }
+    }
+
+    impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for LruCache<K, V> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_map()
+                .entries(self.entries.iter().map(|(key, value)| (key, value)))
+                .finish()
+        }
+    }
+
+    struct LfuBucket<K, V> {
+        frequency: usize,
+        // Least recently used first
+        items: DoublyLinkedList<(K, V)>,
+    }
+
+    struct LfuEntry<K, V> {
+        bucket: Handle<LfuBucket<K, V>>,
+        handle: Handle<(K, V)>,
+    }
+
+    impl<K, V> Clone for LfuEntry<K, V> {
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+
+    impl<K, V> Copy for LfuEntry<K, V> {}
+
+    // Buckets of entries with the same use count, linked in increasing count and only kept
+    // while they have entries. Eviction takes the least recently used entry of the front
+    // bucket, and a use moves an entry into the bucket right after its own, so every
+    // operation is O(1).
+    pub struct LfuCache<K, V> {
+        buckets: DoublyLinkedList<LfuBucket<K, V>>,
+        entries: HashMap<K, LfuEntry<K, V>>,
+        capacity: usize,
+        on_evict: Option<EvictionCallback<K, V>>,
+    }
+
+    impl<K: Hash + Eq + Clone, V> LfuCache<K, V> {
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                buckets: DoublyLinkedList::new(),
+                entries: HashMap::with_capacity(capacity),
+                capacity,
+                on_evict: None,
+            }
+        }
+
+        pub fn on_evict(mut self, callback: impl FnMut(K, V) + 'static) -> Self {
+            self.on_evict = Some(Box::new(callback));
+            self
+        }
+
+        // Moves the entry up to the next use count and returns it
+        fn touch(&mut self, key: &K) -> Option<LfuEntry<K, V>> {
+            let entry = self.entries.get_mut(key)?;
+            // SAFETY: an entry's bucket handle points into `buckets` and its handle into the
+            // items of that bucket, for as long as the entry is in the map
+            unsafe {
+                let bucket = self.buckets.get_mut(entry.bucket);
+                let frequency = bucket.frequency + 1;
+                let item = bucket.items.remove(entry.handle);
+                let next = match self.buckets.next_handle(entry.bucket) {
+                    Some(next) if self.buckets.get(next).frequency == frequency => next,
+                    _ => {
+                        let bucket = LfuBucket { frequency, items: DoublyLinkedList::new() };
+                        self.buckets.insert_after_handle(entry.bucket, bucket)
+                    }
+                };
+                if self.buckets.get(entry.bucket).items.is_empty() {
+                    self.buckets.remove(entry.bucket);
+                }
+                entry.bucket = next;
+                entry.handle = self.buckets.get_mut(next).items.push_back_handle(item);
+            }
+            Some(*entry)
+        }
+
+        pub fn get(&mut self, key: &K) -> Option<&V> {
+            self.touch(key)?;
+            self.peek(key)
+        }
+
+        pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+            let entry = self.touch(key)?;
+            // SAFETY: as in `touch`
+            unsafe { Some(&mut self.buckets.get_mut(entry.bucket).items.get_mut(entry.handle).1) }
+        }
+
+        // Looks the value up without counting a use
+        pub fn peek(&self, key: &K) -> Option<&V> {
+            let entry = self.entries.get(key)?;
+            // SAFETY: as in `touch`
+            unsafe { Some(&self.buckets.get(entry.bucket).items.get(entry.handle).1) }
+        }
+
+        pub fn frequency(&self, key: &K) -> Option<usize> {
+            let entry = self.entries.get(key)?;
+            // SAFETY: as in `touch`
+            unsafe { Some(self.buckets.get(entry.bucket).frequency) }
+        }
+
+        pub fn contains(&self, key: &K) -> bool {
+            self.entries.contains_key(key)
+        }
+
+        // Updating an existing key counts as a use, new keys start at one use.
+        // Returns the previous value when the key was already cached. As in `LruCache`, a
+        // cache with no capacity stores nothing and does not call `on_evict`.
+        pub fn put(&mut self, key: K, value: V) -> Option<V> {
+            if let Some(value_slot) = self.get_mut(&key) {
+                return Some(std::mem::replace(value_slot, value));
+            }
+            if self.capacity == 0 {
+                return None;
+            }
+            if self.entries.len() == self.capacity {
+                self.evict(self.capacity - 1);
+            }
+
+            let bucket = match self.buckets.front_handle() {
+                // SAFETY: the front handle was just taken from the list
+                Some(front) if unsafe { self.buckets.get(front).frequency } == 1 => front,
+                _ => {
+                    let bucket = LfuBucket { frequency: 1, items: DoublyLinkedList::new() };
+                    self.buckets.push_front_handle(bucket)
+                }
+            };
+            // SAFETY: as above
+            let handle = unsafe { self.buckets.get_mut(bucket).items.push_back_handle((key.clone(), value)) };
+            self.entries.insert(key, LfuEntry { bucket, handle });
+            None
+        }
+
+        pub fn remove(&mut self, key: &K) -> Option<V> {
+            let entry = self.entries.remove(key)?;
+            // SAFETY: as in `touch`, and the entry is gone from the map
+            unsafe {
+                let bucket = self.buckets.get_mut(entry.bucket);
+                let (_, value) = bucket.items.remove(entry.handle);
+                if bucket.items.is_empty() {
+                    self.buckets.remove(entry.bucket);
+                }
+                Some(value)
+            }
+        }
+
+        // The least frequently used entry, the least recently used one among ties
+        pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+            let front = self.buckets.front_handle()?;
+            // SAFETY: the front handle was just taken from the list
+            let bucket = unsafe { self.buckets.get_mut(front) };
+            let (key, value) = bucket.items.pop_front().unwrap();
+            if bucket.items.is_empty() {
+                self.buckets.pop_front();
+            }
+            self.entries.remove(&key);
+            Some((key, value))
+        }
+
+        pub fn resize(&mut self, capacity: usize) {
+            self.capacity = capacity;
+            self.evict(capacity);
+        }
+
+        fn evict(&mut self, capacity: usize) {
+            while self.entries.len() > capacity {
+                let (key, value) = self.pop_lfu().unwrap();
+                if let Some(callback) = self.on_evict.as_mut() {
+                    callback(key, value);
+                }
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.capacity
+        }
+    }
+
+    impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for LfuCache<K, V> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_map()
+                .entries(self.buckets.iter().map(|bucket| (bucket.frequency, &bucket.items)))
+                .finish()
+        }
+    }
+
+    pub fn run() {
+        let mut lru = LruCache::new(2).on_evict(|key, value| println!("evicted {} = {}", key, value));
+        lru.put("a", 1);
+        lru.put("b", 2);
+        println!("{:?}", lru.get(&"a"));
+        lru.put("c", 3);
+        println!("{:?} {:?}", lru, lru.peek(&"b"));
+        lru.resize(1);
+        println!("{:?}", lru);
+        println!("{:?} {}", lru.pop_lru(), lru.len());
+
+        let mut lfu = LfuCache::new(2).on_evict(|key, value| println!("evicted {} = {}", key, value));
+        lfu.put("a", 1);
+        lfu.put("b", 2);
+        lfu.get(&"a");
+        lfu.get(&"a");
+        lfu.put("c", 3);
+        println!("{:?} {:?}", lfu, lfu.frequency(&"a"));
+        println!("{:?}", lfu.pop_lfu());
     }
 }
@@ -485,45 +485,54 @@ pub mod avl {
 }
 
 pub mod heaps {
+    use std::cmp::{Ordering, Reverse};
+    use std::collections::{HashMap, VecDeque};
+    use std::hash::Hash;
+
     #[derive(Debug)]
-    struct Heap {
-        items: Vec<i32>,
-        size: usize,
+    pub struct Heap<T> {
+        items: Vec<T>,
+        capacity: usize,
     }
 
-    impl Heap {
+    impl<T: Ord> Heap<T> {
         pub fn new(length: usize) -> Self {
             Self {
-                items: vec![0; length],
-                size: 0,
+                items: Vec::with_capacity(length),
+                capacity: length,
+            }
+        }
+
+        pub fn unbounded() -> Self {
+            Self {
+                items: Vec::new(),
+                capacity: usize::MAX,
             }
         }
 
-        pub fn insert(&mut self, value: i32) {
+        pub fn insert(&mut self, value: T) {
             if self.is_full() {
                 return;
             }
 
-            self.items[self.size] = value;
-            self.size += 1;
+            self.items.push(value);
             self.bubble_up();
         }
 
-        fn remove(&mut self) -> i32 {
+        pub fn remove(&mut self) -> Option<T> {
             if self.is_empty() {
-                return -1;
+                return None;
             }
 
-            let root = self.items[0]; // 2
-            self.items[0] = self.items[self.size - 1]; // 5
-            self.size -= 1; // 4 -> [5, 17, 10, 4, 5]
+            // The last item takes the place of the root and sinks down
+            let root = self.items.swap_remove(0);
             self.bubble_down();
-            root
+            Some(root)
         }
 
         fn bubble_down(&mut self) {
             let mut index = 0;
-            while index <= self.size && !self.is_valid_parent(index) {
+            while index < self.items.len() && !self.is_valid_parent(index) {
                 let larger_child_index = self.largest_child_index(index);
 
                 self.swap(index, larger_child_index);
@@ -548,11 +557,11 @@ pub mod heaps {
         }
 
         fn has_left_child(&self, index: usize) -> bool {
-            self.left_child_index(index) <= self.size
+            self.left_child_index(index) < self.items.len()
         }
 
         fn has_right_child(&self, index: usize) -> bool {
-            self.right_child_index(index) <= self.size
+            self.right_child_index(index) < self.items.len()
         }
 
         fn is_valid_parent(&self, index: usize) -> bool {
@@ -560,20 +569,20 @@ pub mod heaps {
                 return true;
             }
 
-            let is_valid = self.items[index] >= self.left_child(index);
+            let is_valid = self.items[index] >= *self.left_child(index);
             if self.has_right_child(index) {
-                is_valid && self.items[index] >= self.right_child(index)
+                is_valid && self.items[index] >= *self.right_child(index)
             } else {
                 is_valid
             }
         }
 
-        fn left_child(&self, index: usize) -> i32 {
-            self.items[self.left_child_index(index)]
+        fn left_child(&self, index: usize) -> &T {
+            &self.items[self.left_child_index(index)]
         }
 
-        fn right_child(&self, index: usize) -> i32 {
-            self.items[self.right_child_index(index)]
+        fn right_child(&self, index: usize) -> &T {
+            &self.items[self.right_child_index(index)]
         }
 
         fn left_child_index(&self, index: usize) -> usize {
@@ -584,16 +593,20 @@ pub mod heaps {
             index * 2 + 2
         }
 
-        fn is_full(&self) -> bool {
-            self.size == self.items.len()
+        pub fn is_full(&self) -> bool {
+            self.items.len() == self.capacity
         }
 
-        fn is_empty(&self) -> bool {
-            self.size == 0
+        pub fn is_empty(&self) -> bool {
+            self.items.is_empty()
+        }
+
+        pub fn len(&self) -> usize {
+            self.items.len()
         }
 
         fn bubble_up(&mut self) {
-            let mut index = self.size - 1;
+            let mut index = self.items.len() - 1;
             while index > 0 && self.items[index] > self.items[self.parent(index)] {
                 self.swap(index, self.parent(index));
                 index = self.parent(index);
@@ -601,9 +614,7 @@ pub mod heaps {
         }
 
         fn swap(&mut self, first: usize, second: usize) {
-            let temp = self.items[first];
-            self.items[first] = self.items[second];
-            self.items[second] = temp;
+            self.items.swap(first, second);
         }
 
         fn parent(&self, index: usize) -> usize {
@@ -611,19 +622,16 @@ pub mod heaps {
         }
 
         fn last_parent(&self) -> usize {
-            self.size / 2 - 1
+            self.items.len() / 2 - 1
         }
 
-        pub fn max(&self) -> i32 {
-            if self.is_empty() {
-                return -1;
-            }
-            self.items[0]
+        pub fn max(&self) -> Option<&T> {
+            self.items.first()
         }
     }
 
     struct PriorityQueueWithHeap {
-        heap: Heap,
+        heap: Heap<i32>,
     }
 
     impl PriorityQueueWithHeap {
@@ -637,7 +645,7 @@ pub mod heaps {
             self.heap.insert(item);
         }
 
-        fn dequeue(&mut self) -> i32 {
+        fn dequeue(&mut self) -> Option<i32> {
             self.heap.remove()
         }
 
@@ -646,952 +654,4856 @@ pub mod heaps {
         }
     }
 
-    fn heapify(numbers: &mut Vec<i32>) {
-        let len = numbers.len();
-        let last_parent_index = len / 2 - 1;
-        for i in (0..last_parent_index).rev() {
-            _heapify(numbers, i);
+    // Max-priority queue interface shared by the heap variants, wrap items in
+    // `Reverse` for min-first order (e.g. Dijkstra)
+    pub trait PriorityQueue<T: Ord> {
+        fn push(&mut self, item: T);
+        fn pop(&mut self) -> Option<T>;
+        fn peek(&self) -> Option<&T>;
+        fn len(&self) -> usize;
+
+        fn is_empty(&self) -> bool {
+            self.len() == 0
         }
-        println!("{:?}", numbers)
     }
 
-    fn _heapify(numbers: &mut Vec<i32>, index: usize) {
-        let mut larger_index = index;
-        let left_index = index * 2 + 1;
-        if left_index < numbers.len() && numbers[left_index] > numbers[larger_index] {
-            larger_index = left_index;
+    // Unlike `insert`, a push never drops the item, a full heap doubles its capacity first
+    impl<T: Ord> PriorityQueue<T> for Heap<T> {
+        fn push(&mut self, item: T) {
+            if self.is_full() {
+                self.capacity = self.capacity.saturating_mul(2).max(1);
+            }
+            self.insert(item);
         }
 
-        let right_index = index * 2 + 2;
-        if right_index < numbers.len() && numbers[right_index] > numbers[larger_index] {
-            larger_index = right_index;
+        fn pop(&mut self) -> Option<T> {
+            self.remove()
         }
 
-        if index == larger_index {
-            return;
+        fn peek(&self) -> Option<&T> {
+            self.max()
         }
-        numbers.swap(index, larger_index);
-        _heapify(numbers, larger_index);
-    }
 
-    fn get_kth_largest(numbers: &Vec<i32>, k: i32) -> i32 {
-        if k < 1 || k > numbers.len() as i32 {
-            return -1;
+        fn len(&self) -> usize {
+            self.items.len()
         }
-        let mut heap = Heap::new(numbers.len());
-        numbers.iter().for_each(|&number| heap.insert(number));
-        for _ in 0..k - 1 {
-            heap.remove();
-        }
-        println!("{:?}", heap);
-        heap.max()
-    }
-
-    pub fn run() {
-        let mut heaps = Heap::new(10);
-        heaps.insert(10);
-        heaps.insert(5);
-        heaps.insert(17);
-        heaps.insert(4);
-        heaps.insert(22);
-        heaps.remove();
-        println!("{:?}", heaps);
-        // let mut numbers = vec![5, 3, 10, 1, 4, 2];
-        // let mut heap2 = Heap::new(numbers.len());
-        // numbers.iter().for_each(|&number| heap2.insert(number));
-
-        // while !heap2.is_empty() {
-        //     println!("{:?}", heap2.remove());
-        // }
-        //
-        // for i in (0..numbers.len()) {
-        //     numbers[i] = heap2.remove();
-        // }
-        // println!("{:?}", numbers);
-
-        // Heapify
-        let mut numbers = vec![5, 3, 8, 4, 1, 2];
-        // println!("{:?}", heapify(&mut numbers));
-
-        println!("{:?}", get_kth_largest(&numbers, 5));
     }
-}
 
-pub mod tries {
-    use serde::Serialize;
-    use std::collections::HashMap;
-
-    const ALPHABET_SIZE: usize = 26;
-    #[derive(Debug, Serialize)]
-    struct TrieNode {
-        children: HashMap<char, TrieNode>,
-        is_end_of_word: bool,
+    // Heap where every node has up to `arity` children: shallower than a binary heap,
+    // so pushes are cheaper while pops compare more children per level
+    #[derive(Debug, Clone)]
+    pub struct DaryHeap<T> {
+        items: Vec<T>,
+        arity: usize,
     }
 
-    impl TrieNode {
-        pub fn new() -> Self {
+    impl<T: Ord> DaryHeap<T> {
+        pub fn new(arity: usize) -> Self {
+            assert!(arity >= 2, "a heap needs an arity of at least 2");
             Self {
-                children: HashMap::new(),
-                is_end_of_word: false,
+                items: Vec::new(),
+                arity,
             }
         }
 
-        pub fn insert(&mut self, value: &str) {
-            let mut current = self;
-            for c in value.chars() {
-                current = current.children.entry(c).or_insert_with(TrieNode::new);
-            }
+        fn child_index(&self, index: usize, nth: usize) -> usize {
+            index * self.arity + nth + 1
+        }
 
-            current.is_end_of_word = true;
+        fn parent(&self, index: usize) -> usize {
+            (index - 1) / self.arity
         }
 
-        fn has_children(&self, c: &char) -> bool {
-            self.children.contains_key(&c)
+        fn largest_child_index(&self, index: usize) -> Option<usize> {
+            let first = self.child_index(index, 0);
+            let last = (first + self.arity).min(self.items.len());
+            (first..last).max_by(|&first, &second| self.items[first].cmp(&self.items[second]))
         }
 
-        fn get_children(&self, c: &char) -> Option<&TrieNode> {
-            self.children.get(c)
+        fn bubble_up(&mut self, mut index: usize) {
+            while index > 0 && self.items[index] > self.items[self.parent(index)] {
+                let parent = self.parent(index);
+                self.items.swap(index, parent);
+                index = parent;
+            }
         }
 
-        pub fn contains(&self, value: &str) -> bool {
-            let mut current = self;
-            for c in value.chars() {
-                if !current.has_children(&c) {
-                    return false;
+        fn bubble_down(&mut self, mut index: usize) {
+            while let Some(child) = self.largest_child_index(index) {
+                if self.items[child] <= self.items[index] {
+                    break;
                 }
-                current = current.get_children(&c).unwrap();
+                self.items.swap(index, child);
+                index = child;
             }
-            current.is_end_of_word
         }
+    }
 
-        pub fn traverse(&self) {
-            self.traverse_(' ')
+    impl<T: Ord> PriorityQueue<T> for DaryHeap<T> {
+        fn push(&mut self, item: T) {
+            self.items.push(item);
+            self.bubble_up(self.items.len() - 1);
         }
-        fn traverse_(&self, c: char) {
-            if self.is_end_of_word {
-                println!("End of word");
-            }
 
-            for (key, value) in &self.children {
-                println!("{}", key);
-                value.traverse_(*key);
+        fn pop(&mut self) -> Option<T> {
+            if self.items.is_empty() {
+                return None;
             }
+            let root = self.items.swap_remove(0);
+            self.bubble_down(0);
+            Some(root)
         }
 
-        pub fn remove(&mut self, value: String) {
-            self.remove_(value, 0);
+        fn peek(&self) -> Option<&T> {
+            self.items.first()
         }
 
-        fn remove_(&mut self, value: String, index: usize) {
-            if index == value.len() {
-                self.is_end_of_word = false;
-                return;
-            }
-            let c = value.chars().nth(index).unwrap();
-            if let Some(child) = self.children.get_mut(&c) {
-                child.remove_(value.clone(), index + 1);
-                if child.children.is_empty() && !child.is_end_of_word {
-                    self.children.remove(&c);
-                }
-            }
+        fn len(&self) -> usize {
+            self.items.len()
         }
+    }
 
-        pub fn find_words(&mut self, prefix: String) -> Vec<String> {
-            let mut words: Vec<String> = Vec::new();
-            if let Some(node) = self.find_last_node_of(&prefix) {
-                println!("{}", node.to_json());
-                node.find_words_(&prefix, &mut words);
-            }
-            words
-        }
+    #[derive(Debug)]
+    struct PairingNode<T> {
+        item: T,
+        children: Vec<Box<PairingNode<T>>>,
+    }
 
-        fn find_words_(&self, prefix: &String, words: &mut Vec<String>) {
-            if self.is_end_of_word {
-                words.push(prefix.clone());
-            }
+    // Heap ordered tree with O(1) push and meld; pop pairs up the root's children
+    // left to right and then melds the pairs right to left, O(log n) amortized
+    #[derive(Debug)]
+    pub struct PairingHeap<T> {
+        root: Option<Box<PairingNode<T>>>,
+        len: usize,
+    }
 
-            for (c, child) in &self.children {
-                let mut new_prefix = prefix.clone();
-                new_prefix.push(c.clone());
-                child.find_words_(&new_prefix, words);
+    impl<T: Ord> PairingHeap<T> {
+        pub fn new() -> Self {
+            Self { root: None, len: 0 }
+        }
+
+        pub fn meld(mut self, mut other: Self) -> Self {
+            let root = Self::link(self.root.take(), other.root.take());
+            Self {
+                root,
+                len: self.len + other.len,
             }
         }
 
-        fn find_last_node_of(&self, prefix: &String) -> Option<&TrieNode> {
-            let mut current = self;
-            for ch in prefix.chars() {
-                let child = current.children.get(&ch);
-                match child {
-                    Some(child) => {
-                        current = child;
+        // The larger root adopts the other tree as its newest child
+        fn link(first: Option<Box<PairingNode<T>>>, second: Option<Box<PairingNode<T>>>) -> Option<Box<PairingNode<T>>> {
+            match (first, second) {
+                (Some(mut first), Some(mut second)) => {
+                    if first.item >= second.item {
+                        first.children.push(second);
+                        Some(first)
+                    } else {
+                        second.children.push(first);
+                        Some(second)
                     }
-                    None => return None,
                 }
+                (first, None) => first,
+                (None, second) => second,
             }
-            Some(current)
         }
 
-        pub fn count_words(&self) -> i32 {
-            let mut count = 0;
-            self.count_words_(&mut count);
-            count
+        fn merge_pairs(children: Vec<Box<PairingNode<T>>>) -> Option<Box<PairingNode<T>>> {
+            let mut pairs = Vec::with_capacity(children.len() / 2 + 1);
+            let mut children = children.into_iter();
+            while let Some(first) = children.next() {
+                pairs.push(Self::link(Some(first), children.next()));
+            }
+            pairs.into_iter().rev().fold(None, |merged, pair| Self::link(pair, merged))
         }
+    }
 
-        fn count_words_(&self, count: &mut i32) {
-            let mut current = self;
-            for (_, child) in &current.children {
-                if child.is_end_of_word {
-                    *count += 1;
-                }
-                child.count_words_(count);
-                current = child;
-            }
+    impl<T: Ord> Default for PairingHeap<T> {
+        fn default() -> Self {
+            Self::new()
         }
+    }
 
-        pub fn longest_common_prefix(&mut self, words: Vec<String>) -> String {
-            if words.is_empty() {
-                return String::new();
-            }
+    impl<T: Ord> PriorityQueue<T> for PairingHeap<T> {
+        fn push(&mut self, item: T) {
+            let node = Box::new(PairingNode {
+                item,
+                children: Vec::new(),
+            });
+            self.root = Self::link(self.root.take(), Some(node));
+            self.len += 1;
+        }
 
-            let mut trie = TrieNode::new();
-            for word in &words {
-                trie.insert(word);
-            }
+        fn pop(&mut self) -> Option<T> {
+            let root = self.root.take()?;
+            let PairingNode { item, children } = *root;
+            self.root = Self::merge_pairs(children);
+            self.len -= 1;
+            Some(item)
+        }
 
-            let mut prefix = String::new();
-            let mut current = &trie;
+        fn peek(&self) -> Option<&T> {
+            self.root.as_ref().map(|root| &root.item)
+        }
 
-            while current.children.len() == 1 && !current.is_end_of_word {
-                let (c, next_node) = current.children.iter().next().unwrap();
-                prefix.push(*c);
-                current = next_node;
-            }
+        fn len(&self) -> usize {
+            self.len
+        }
+    }
 
-            match self.contains(prefix.as_str()) {
-                true => prefix,
-                false => String::new(),
+    // Trees can get deep after many pushes, so drop them without recursion
+    impl<T> Drop for PairingHeap<T> {
+        fn drop(&mut self) {
+            let mut stack: Vec<Box<PairingNode<T>>> = self.root.take().into_iter().collect();
+            while let Some(mut node) = stack.pop() {
+                stack.append(&mut node.children);
             }
         }
+    }
 
-        fn to_json(&self) -> String {
-            serde_json::to_string_pretty(&self).unwrap()
+    // Unbounded like `Heap::unbounded`, so later inserts are kept
+    impl<T: Ord> From<Vec<T>> for Heap<T> {
+        fn from(mut items: Vec<T>) -> Self {
+            heapify(&mut items);
+            Self {
+                items,
+                capacity: usize::MAX,
+            }
         }
     }
-    pub fn run() {
-        // let mut trie = TrieNode::new();
-        // trie.insert("car");
-        // trie.insert("care");
-        // trie.remove("care".to_string());
-        // // trie.traverse();
-        // println!("{}", trie.to_json());
-        let mut trie = TrieNode::new();
-        trie.insert("car");
-        trie.insert("careful");
-        trie.insert("card");
-        trie.insert("care");
-        trie.insert("egg");
-        trie.find_words("egg".to_string());
-        // println!("{:?}", trie.find_words("e".to_string()));
-        // println!("{:?}", trie.count_words());
-        println!(
-            "{:?}",
-            trie.longest_common_prefix(vec!["god".to_string(), "go".to_string()])
-        );
-    }
-}
 
-pub mod graphs {
-    use std::collections::{HashMap, HashSet};
+    // Grows the capacity to fit the new items and restores the heap bottom-up,
+    // O(n) for the whole batch instead of bubbling each item up
+    impl<T: Ord> Extend<T> for Heap<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            self.items.extend(iter);
+            self.capacity = self.capacity.max(self.items.len());
+            heapify(&mut self.items);
+        }
+    }
 
-    macro_rules! unwrap_or_return {
-        ($option:expr) => {
-            match $option {
-                Some(value) => value,
-                None => return,
-            }
-        };
+    #[derive(Debug)]
+    struct LeftistNode<T> {
+        item: T,
+        // Length of the path to the nearest missing child, never larger on the left
+        rank: usize,
+        left: Option<Box<LeftistNode<T>>>,
+        right: Option<Box<LeftistNode<T>>>,
     }
 
+    // Mergeable max-heap: the right spine is at most log n long and melding only walks
+    // the right spines of both heaps
     #[derive(Debug)]
-    pub struct Stack<T> {
-        elements: Vec<T>,
+    pub struct LeftistHeap<T> {
+        root: Option<Box<LeftistNode<T>>>,
+        len: usize,
     }
 
-    impl<T> Stack<T> {
+    impl<T: Ord> LeftistHeap<T> {
         pub fn new() -> Self {
+            Self { root: None, len: 0 }
+        }
+
+        pub fn meld(mut self, mut other: Self) -> Self {
+            let root = Self::merge(self.root.take(), other.root.take());
             Self {
-                elements: Vec::new(),
+                root,
+                len: self.len + other.len,
             }
         }
 
-        pub fn push(&mut self, item: T) {
-            self.elements.push(item);
+        fn rank(node: &Option<Box<LeftistNode<T>>>) -> usize {
+            node.as_ref().map_or(0, |node| node.rank)
         }
 
-        pub fn pop(&mut self) -> Option<T> {
-            self.elements.pop()
+        fn merge(first: Option<Box<LeftistNode<T>>>, second: Option<Box<LeftistNode<T>>>) -> Option<Box<LeftistNode<T>>> {
+            let (mut first, mut second) = match (first, second) {
+                (Some(first), Some(second)) => (first, second),
+                (first, None) => return first,
+                (None, second) => return second,
+            };
+            if first.item < second.item {
+                std::mem::swap(&mut first, &mut second);
+            }
+            first.right = Self::merge(first.right.take(), Some(second));
+            if Self::rank(&first.left) < Self::rank(&first.right) {
+                std::mem::swap(&mut first.left, &mut first.right);
+            }
+            first.rank = Self::rank(&first.right) + 1;
+            Some(first)
         }
 
-        pub fn peek(&self) -> Option<&T> {
-            self.elements.last()
+        fn singleton(item: T) -> Self {
+            let node = Box::new(LeftistNode {
+                item,
+                rank: 1,
+                left: None,
+                right: None,
+            });
+            Self { root: Some(node), len: 1 }
         }
+    }
 
-        pub fn is_empty(&self) -> bool {
-            self.elements.is_empty()
+    impl<T: Ord> Default for LeftistHeap<T> {
+        fn default() -> Self {
+            Self::new()
         }
     }
 
-    #[derive(Debug, Eq, Hash, PartialEq, Clone)]
-    struct GraphNode {
-        label: String,
+    impl<T: Ord> PriorityQueue<T> for LeftistHeap<T> {
+        fn push(&mut self, item: T) {
+            let heap = std::mem::take(self);
+            *self = heap.meld(Self::singleton(item));
+        }
+
+        fn pop(&mut self) -> Option<T> {
+            let root = self.root.take()?;
+            let LeftistNode { item, left, right, .. } = *root;
+            self.root = Self::merge(left, right);
+            self.len -= 1;
+            Some(item)
+        }
+
+        fn peek(&self) -> Option<&T> {
+            self.root.as_ref().map(|root| &root.item)
+        }
+
+        fn len(&self) -> usize {
+            self.len
+        }
     }
-    impl GraphNode {
-        pub fn new(label: String) -> Self {
-            Self { label }
+
+    // Melds singletons in rounds of pairs, O(n) in total
+    impl<T: Ord> From<Vec<T>> for LeftistHeap<T> {
+        fn from(items: Vec<T>) -> Self {
+            let mut queue: VecDeque<Self> = items.into_iter().map(Self::singleton).collect();
+            while queue.len() > 1 {
+                let first = queue.pop_front().unwrap();
+                let second = queue.pop_front().unwrap();
+                queue.push_back(first.meld(second));
+            }
+            queue.pop_front().unwrap_or_default()
         }
     }
 
-    #[derive(Debug)]
-    struct Graph {
-        node: HashMap<String, GraphNode>,
-        adjacency_list: HashMap<String, Vec<GraphNode>>,
+    impl<T: Ord> Extend<T> for LeftistHeap<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            let heap = std::mem::take(self);
+            *self = heap.meld(Self::from(iter.into_iter().collect::<Vec<T>>()));
+        }
     }
 
-    impl Graph {
-        pub fn new() -> Self {
-            Self {
-                node: HashMap::new(),
-                adjacency_list: HashMap::new(),
+    // The left spine can be as long as the heap, so drop it without recursion
+    impl<T> Drop for LeftistHeap<T> {
+        fn drop(&mut self) {
+            let mut stack: Vec<Box<LeftistNode<T>>> = self.root.take().into_iter().collect();
+            while let Some(mut node) = stack.pop() {
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
             }
         }
+    }
 
-        pub fn add_node(&mut self, label: String) {
-            let node = GraphNode::new(label.to_owned());
-            self.node.entry(label.to_owned()).or_insert(node);
-            self.adjacency_list
-                .entry(label.to_owned())
-                .or_insert(Vec::new());
+    // Double-ended priority queue: nodes on even levels (the root is level 0) are the
+    // smallest of their subtree, nodes on odd levels the largest
+    #[derive(Debug, Clone)]
+    pub struct MinMaxHeap<T> {
+        items: Vec<T>,
+    }
+
+    impl<T: Ord> MinMaxHeap<T> {
+        pub fn new() -> Self {
+            Self { items: Vec::new() }
         }
 
-        pub fn remove_node(&mut self, label: String) {
-            let node = unwrap_or_return!(self.node.get(&label).cloned());
-            let keys = self.adjacency_list.keys().cloned().collect::<Vec<String>>();
-            for key in keys {
-                self.adjacency_list.get_mut(&key).and_then(|list| {
-                    let index = list.iter().position(|n| n.eq(&node));
-                    match index {
-                        Some(index) => {
-                            list.remove(index);
-                            Some(())
-                        }
-                        None => None,
-                    }
-                });
-                self.adjacency_list.remove(&label);
-                self.node.remove(&label);
+        pub fn push(&mut self, item: T) {
+            self.items.push(item);
+            let index = self.items.len() - 1;
+            if index == 0 {
+                return;
+            }
+
+            // Settle which kind of level the item belongs to, then move it up among
+            // the grandparents of that kind
+            let parent = self.parent(index);
+            let wanted = self.level_order(index);
+            if self.items[parent].cmp(&self.items[index]) == wanted {
+                self.swap(index, parent);
+                self.bubble_up(parent, wanted.reverse());
+            } else {
+                self.bubble_up(index, wanted);
             }
         }
 
-        pub fn add_edge(&mut self, from: String, to: String) {
-            let to_node = unwrap_or_return!(self.node.get(&to).cloned());
-            self.adjacency_list.get_mut(&from).and_then(|list| {
-                list.push(to_node);
-                Some(())
-            });
+        pub fn pop_min(&mut self) -> Option<T> {
+            if self.items.is_empty() {
+                return None;
+            }
+            let min = self.items.swap_remove(0);
+            self.trickle_down(0);
+            Some(min)
         }
 
-        pub fn remove_edge(&mut self, from: String, to: String) {
-            let to_node = unwrap_or_return!(self.node.get(&to).cloned());
+        pub fn pop_max(&mut self) -> Option<T> {
+            let index = self.max_index()?;
+            let max = self.items.swap_remove(index);
+            self.trickle_down(index);
+            Some(max)
+        }
+
+        pub fn peek_min(&self) -> Option<&T> {
+            self.items.first()
+        }
+
+        pub fn peek_max(&self) -> Option<&T> {
+            self.max_index().map(|index| &self.items[index])
+        }
+
+        pub fn len(&self) -> usize {
+            self.items.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.items.is_empty()
+        }
+
+        // The largest item is the root when alone, otherwise one of its children
+        fn max_index(&self) -> Option<usize> {
+            match self.items.len() {
+                0 => None,
+                1 => Some(0),
+                2 => Some(1),
+                _ if self.items[1] >= self.items[2] => Some(1),
+                _ => Some(2),
+            }
+        }
+
+        // Less on min levels and Greater on max levels: how a node compares to its subtree
+        fn level_order(&self, index: usize) -> Ordering {
+            if (index + 1).ilog2().is_multiple_of(2) {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }
+
+        fn bubble_up(&mut self, mut index: usize, wanted: Ordering) {
+            while index > 2 {
+                let grandparent = self.parent(self.parent(index));
+                if self.items[index].cmp(&self.items[grandparent]) != wanted {
+                    break;
+                }
+                self.swap(index, grandparent);
+                index = grandparent;
+            }
+        }
+
+        fn trickle_down(&mut self, mut index: usize) {
+            let wanted = self.level_order(index);
+            while let Some(best) = self.best_descendant(index, wanted) {
+                if self.items[best].cmp(&self.items[index]) != wanted {
+                    return;
+                }
+                self.swap(index, best);
+                if best <= self.right_child_index(index) {
+                    // A child is on the opposite kind of level and has no grandchildren
+                    return;
+                }
+                // The item that came down may not respect its new parent on the other level
+                let parent = self.parent(best);
+                if self.items[parent].cmp(&self.items[best]) == wanted {
+                    self.swap(best, parent);
+                }
+                index = best;
+            }
+        }
+
+        // Smallest (or largest) among the children and grandchildren
+        fn best_descendant(&self, index: usize, wanted: Ordering) -> Option<usize> {
+            let left = self.left_child_index(index);
+            let right = self.right_child_index(index);
+            let grandchildren = self.left_child_index(left)..=self.right_child_index(right);
+            [left, right]
+                .into_iter()
+                .chain(grandchildren)
+                .filter(|&descendant| descendant < self.items.len())
+                .reduce(|best, descendant| {
+                    if self.items[descendant].cmp(&self.items[best]) == wanted {
+                        descendant
+                    } else {
+                        best
+                    }
+                })
+        }
+
+        fn left_child_index(&self, index: usize) -> usize {
+            index * 2 + 1
+        }
+
+        fn right_child_index(&self, index: usize) -> usize {
+            index * 2 + 2
+        }
+
+        fn parent(&self, index: usize) -> usize {
+            (index - 1) / 2
+        }
+
+        fn swap(&mut self, first: usize, second: usize) {
+            self.items.swap(first, second);
+        }
+    }
+
+    impl<T: Ord> Default for MinMaxHeap<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    // Median of everything pushed so far: the lower half sits in a max-heap and the upper
+    // half in a min-heap, with the lower half holding the extra item for odd counts
+    #[derive(Debug)]
+    pub struct RunningMedian<T> {
+        low: Heap<T>,
+        high: Heap<Reverse<T>>,
+    }
+
+    impl<T: Ord> RunningMedian<T> {
+        pub fn new() -> Self {
+            Self {
+                low: Heap::unbounded(),
+                high: Heap::unbounded(),
+            }
+        }
+
+        pub fn push(&mut self, item: T) {
+            match self.low.max() {
+                Some(top) if item > *top => self.high.insert(Reverse(item)),
+                _ => self.low.insert(item),
+            }
+
+            if self.low.len() > self.high.len() + 1 {
+                let moved = self.low.remove().unwrap();
+                self.high.insert(Reverse(moved));
+            } else if self.low.len() < self.high.len() {
+                let Reverse(moved) = self.high.remove().unwrap();
+                self.low.insert(moved);
+            }
+        }
+
+        // The lower median for even counts, see `middle` for both
+        pub fn median(&self) -> Option<&T> {
+            self.low.max()
+        }
+
+        // The two middle items, the same one twice for odd counts
+        pub fn middle(&self) -> Option<(&T, &T)> {
+            let low = self.low.max()?;
+            match self.high.max() {
+                Some(Reverse(high)) if self.low.len() == self.high.len() => Some((low, high)),
+                _ => Some((low, low)),
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.low.len() + self.high.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.low.is_empty()
+        }
+    }
+
+    impl<T: Ord> Default for RunningMedian<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    // Median of the last `size` items. Expired items stay in their heap until they reach
+    // the top, `expired` counts them and the halves are balanced by their live sizes.
+    #[derive(Debug)]
+    pub struct SlidingMedian<T> {
+        low: Heap<T>,
+        high: Heap<Reverse<T>>,
+        low_len: usize,
+        high_len: usize,
+        expired: HashMap<T, usize>,
+        window: VecDeque<T>,
+        size: usize,
+    }
+
+    impl<T: Ord + Hash + Clone> SlidingMedian<T> {
+        pub fn new(size: usize) -> Self {
+            assert!(size > 0, "the window needs room for at least one item");
+            Self {
+                low: Heap::unbounded(),
+                high: Heap::unbounded(),
+                low_len: 0,
+                high_len: 0,
+                expired: HashMap::new(),
+                window: VecDeque::with_capacity(size + 1),
+                size,
+            }
+        }
+
+        pub fn push(&mut self, item: T) {
+            self.window.push_back(item.clone());
+            match self.low.max() {
+                Some(top) if item > *top => {
+                    self.high.insert(Reverse(item));
+                    self.high_len += 1;
+                }
+                _ => {
+                    self.low.insert(item);
+                    self.low_len += 1;
+                }
+            }
+            self.balance();
+
+            if self.window.len() > self.size {
+                let oldest = self.window.pop_front().unwrap();
+                self.expire(oldest);
+            }
+        }
+
+        fn expire(&mut self, item: T) {
+            // Live tops are kept pruned, so the low top tells which half the item is in
+            if item <= *self.low.max().unwrap() {
+                self.low_len -= 1;
+            } else {
+                self.high_len -= 1;
+            }
+            *self.expired.entry(item).or_insert(0) += 1;
+            self.prune();
+            self.balance();
+        }
+
+        fn balance(&mut self) {
+            if self.low_len > self.high_len + 1 {
+                let moved = self.low.remove().unwrap();
+                self.high.insert(Reverse(moved));
+                self.low_len -= 1;
+                self.high_len += 1;
+            } else if self.low_len < self.high_len {
+                let Reverse(moved) = self.high.remove().unwrap();
+                self.low.insert(moved);
+                self.high_len -= 1;
+                self.low_len += 1;
+            }
+            self.prune();
+        }
+
+        // Drops expired items sitting at the top of either heap
+        fn prune(&mut self) {
+            while let Some(top) = self.low.max() {
+                if !Self::take_expired(&mut self.expired, top) {
+                    break;
+                }
+                self.low.remove();
+            }
+            while let Some(Reverse(top)) = self.high.max() {
+                if !Self::take_expired(&mut self.expired, top) {
+                    break;
+                }
+                self.high.remove();
+            }
+        }
+
+        fn take_expired(expired: &mut HashMap<T, usize>, item: &T) -> bool {
+            match expired.get_mut(item) {
+                Some(count) => {
+                    *count -= 1;
+                    if *count == 0 {
+                        expired.remove(item);
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+
+        pub fn median(&self) -> Option<&T> {
+            self.low.max()
+        }
+
+        pub fn middle(&self) -> Option<(&T, &T)> {
+            let low = self.low.max()?;
+            match self.high.max() {
+                Some(Reverse(high)) if self.low_len == self.high_len => Some((low, high)),
+                _ => Some((low, low)),
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.window.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.window.is_empty()
+        }
+    }
+
+    pub fn heapify<T: Ord>(numbers: &mut [T]) {
+        // Every index from the last parent down to the root, the leaves are already heaps
+        let len = numbers.len();
+        for i in (0..len / 2).rev() {
+            _heapify(numbers, i);
+        }
+    }
+
+    fn _heapify<T: Ord>(numbers: &mut [T], index: usize) {
+        let mut larger_index = index;
+        let left_index = index * 2 + 1;
+        if left_index < numbers.len() && numbers[left_index] > numbers[larger_index] {
+            larger_index = left_index;
+        }
+
+        let right_index = index * 2 + 2;
+        if right_index < numbers.len() && numbers[right_index] > numbers[larger_index] {
+            larger_index = right_index;
+        }
+
+        if index == larger_index {
+            return;
+        }
+        numbers.swap(index, larger_index);
+        _heapify(numbers, larger_index);
+    }
+
+    // In place and ascending: the max-heap root is swapped behind the shrinking heap
+    pub fn heap_sort<T: Ord>(items: &mut [T]) {
+        heapify(items);
+        for end in (1..items.len()).rev() {
+            items.swap(0, end);
+            _heapify(&mut items[..end], 0);
+        }
+    }
+
+    // Moves the k smallest items to the front in ascending order, O(n log k).
+    // The order of the remaining items is unspecified.
+    pub fn partial_sort<T: Ord>(items: &mut [T], k: usize) {
+        let k = k.min(items.len());
+        if k == 0 {
+            return;
+        }
+        // The front k items are a max-heap of the smallest items seen so far
+        heapify(&mut items[..k]);
+        for i in k..items.len() {
+            if items[i] < items[0] {
+                items.swap(0, i);
+                _heapify(&mut items[..k], 0);
+            }
+        }
+        heap_sort(&mut items[..k]);
+    }
+
+    // The item at position n (from 0) if the slice were sorted
+    pub fn select_nth<T: Ord>(items: &mut [T], n: usize) -> Option<&T> {
+        if n >= items.len() {
+            return None;
+        }
+        partial_sort(items, n + 1);
+        items.get(n)
+    }
+
+    // Keeps the k largest items pushed so far in a min-heap of size k
+    #[derive(Debug)]
+    pub struct TopK<T> {
+        heap: Heap<Reverse<T>>,
+    }
+
+    impl<T: Ord> TopK<T> {
+        pub fn new(k: usize) -> Self {
+            Self { heap: Heap::new(k) }
+        }
+
+        pub fn push(&mut self, item: T) {
+            if !self.heap.is_full() {
+                self.heap.insert(Reverse(item));
+                return;
+            }
+            match self.heap.max() {
+                Some(Reverse(smallest)) if item > *smallest => {
+                    self.heap.remove();
+                    self.heap.insert(Reverse(item));
+                }
+                _ => {}
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.heap.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.heap.is_empty()
+        }
+
+        // The smallest of the kept items, the k-th largest once k items were pushed
+        pub fn min(&self) -> Option<&T> {
+            self.heap.max().map(|Reverse(item)| item)
+        }
+
+        // Largest first
+        pub fn into_sorted_vec(mut self) -> Vec<T> {
+            let mut items = Vec::with_capacity(self.heap.len());
+            while let Some(Reverse(item)) = self.heap.remove() {
+                items.push(item);
+            }
+            items.reverse();
+            items
+        }
+    }
+
+    impl<T: Ord> Extend<T> for TopK<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.push(item);
+            }
+        }
+    }
+
+    fn get_kth_largest(numbers: &[i32], k: usize) -> Option<i32> {
+        if k < 1 || k > numbers.len() {
+            return None;
+        }
+        let mut top = TopK::new(k);
+        top.extend(numbers.iter().copied());
+        top.min().copied()
+    }
+
+    pub fn run() {
+        let mut heaps = Heap::new(10);
+        heaps.insert(10);
+        heaps.insert(5);
+        heaps.insert(17);
+        heaps.insert(4);
+        heaps.insert(22);
+        heaps.remove();
+        println!("{:?}", heaps);
+        // let mut numbers = vec![5, 3, 10, 1, 4, 2];
+        // let mut heap2 = Heap::new(numbers.len());
+        // numbers.iter().for_each(|&number| heap2.insert(number));
+
+        // while !heap2.is_empty() {
+        //     println!("{:?}", heap2.remove());
+        // }
+        //
+        // for i in (0..numbers.len()) {
+        //     numbers[i] = heap2.remove();
+        // }
+        // println!("{:?}", numbers);
+
+        // Heapify
+        let mut numbers = vec![5, 3, 8, 4, 1, 2];
+        heapify(&mut numbers);
+        println!("{:?}", numbers);
+
+        println!("{:?}", get_kth_largest(&numbers, 5));
+
+        heap_sort(&mut numbers);
+        println!("{:?}", numbers);
+
+        let mut latencies = vec![120, 45, 300, 87, 12, 230, 66];
+        partial_sort(&mut latencies, 3);
+        println!("{:?}", &latencies[..3]);
+        println!("{:?}", select_nth(&mut latencies, 5));
+
+        let mut top = TopK::new(3);
+        top.extend(vec![120, 45, 300, 87, 12, 230, 66]);
+        println!("{:?}", top.into_sorted_vec());
+
+        let mut dary = DaryHeap::new(4);
+        let mut pairing = PairingHeap::new();
+        for latency in [120, 45, 300, 87] {
+            dary.push(latency);
+            pairing.push(latency);
+        }
+        let mut other = PairingHeap::new();
+        other.push(500);
+        let mut pairing = pairing.meld(other);
+        println!("{:?} {:?}", dary.pop(), dary.peek());
+        println!("{:?} {:?} {}", pairing.pop(), pairing.peek(), pairing.len());
+
+        let mut shard = LeftistHeap::from(vec![120, 45, 300]);
+        shard.extend(vec![87, 12]);
+        let mut merged = shard.meld(LeftistHeap::from(vec![230, 66]));
+        println!("{:?} {:?} {}", merged.pop(), merged.peek(), merged.len());
+
+        let mut heap = Heap::from(vec![5, 3, 8, 4]);
+        heap.extend(vec![10, 1]);
+        heap.insert(9);
+        println!("{:?} {:?}", heap.remove(), heap.max());
+
+        let mut deadlines = MinMaxHeap::new();
+        for deadline in [40, 10, 70, 30, 90, 20] {
+            deadlines.push(deadline);
+        }
+        println!("{:?} {:?}", deadlines.peek_min(), deadlines.peek_max());
+        println!("{:?} {:?} {}", deadlines.pop_min(), deadlines.pop_max(), deadlines.len());
+
+        let mut median = RunningMedian::new();
+        let mut window = SlidingMedian::new(3);
+        for latency in [120, 45, 300, 87, 12, 230] {
+            median.push(latency);
+            window.push(latency);
+            println!("{:?} {:?} {:?}", median.median(), median.middle(), window.median());
+        }
+    }
+}
+
+pub mod tries {
+    use crate::part2::heaps::Heap;
+    use serde::Serialize;
+    use std::cmp::Reverse;
+    use std::collections::{HashMap, HashSet, VecDeque};
+    use std::fmt;
+    use std::hash::Hash;
+    use std::marker::PhantomData;
+
+    const ALPHABET_SIZE: usize = 26;
+
+    // Set of words on top of `TrieMap`, with the search features that need to walk the
+    // entries of the map directly
+    struct TrieNode {
+        words: TrieMap<()>,
+    }
+
+    type WordEntry = TrieEntry<char, (), HashStorage>;
+
+    impl TrieNode {
+        pub fn new() -> Self {
+            Self { words: TrieMap::new() }
+        }
+
+        pub fn insert(&mut self, value: &str) {
+            // A hash map child table has a slot for every char, so no key is rejected
+            self.words.insert(value.chars(), ()).expect("chars always fit a hash map trie");
+        }
+
+        pub fn len(&self) -> usize {
+            self.words.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.words.is_empty()
+        }
+
+        pub fn count_with_prefix(&self, prefix: &str) -> usize {
+            self.words.count_with_prefix(prefix.chars())
+        }
+
+        pub fn contains(&self, value: &str) -> bool {
+            self.words.contains_key(value.chars())
+        }
+
+        pub fn traverse(&self) {
+            Self::traverse_(&self.words.root)
+        }
+        fn traverse_(node: &WordEntry) {
+            if node.value.is_some() {
+                println!("End of word");
+            }
+
+            for (key, value) in &node.children {
+                println!("{}", key);
+                Self::traverse_(value);
+            }
+        }
+
+        pub fn remove(&mut self, value: &str) -> bool {
+            self.words.remove(value.chars()).is_some()
+        }
+
+        pub fn find_words(&self, prefix: String) -> Vec<String> {
+            self.iter_prefix(&prefix).collect()
+        }
+
+        // Words starting with the prefix in lexicographic order, produced one at a time
+        // so `take(n)` only visits the part of the trie it needs
+        pub fn iter_prefix(&self, prefix: &str) -> Words<'_> {
+            let mut stack = Vec::new();
+            if let Some(node) = self.words.find_last_node_of(prefix.chars()) {
+                stack.push((node, prefix.to_string()));
+            }
+            Words { stack }
+        }
+
+        // Stored words within `distance` edits (Levenshtein) of the word, closest first and
+        // alphabetical among equals. Each node extends its parent's distance row by one
+        // character, and subtrees whose whole row is above the bound are skipped.
+        pub fn search_within_distance(&self, word: &str, distance: usize) -> Vec<(String, usize)> {
+            let target = word.chars().collect::<Vec<char>>();
+            let first_row = (0..=target.len()).collect::<Vec<usize>>();
+            let mut results: Vec<(String, usize)> = Vec::new();
+            let root = &self.words.root;
+            if root.value.is_some() && first_row[target.len()] <= distance {
+                results.push((String::new(), first_row[target.len()]));
+            }
+
+            let mut prefix = String::new();
+            for (c, child) in &root.children {
+                Self::search_within_distance_(child, *c, &target, &first_row, distance, &mut prefix, &mut results);
+            }
+            results.sort_by(|first, second| first.1.cmp(&second.1).then_with(|| first.0.cmp(&second.0)));
+            results
+        }
+
+        fn search_within_distance_(
+            node: &WordEntry,
+            c: char,
+            target: &[char],
+            previous_row: &[usize],
+            distance: usize,
+            prefix: &mut String,
+            results: &mut Vec<(String, usize)>,
+        ) {
+            prefix.push(c);
+            let mut row = vec![previous_row[0] + 1];
+            for column in 1..=target.len() {
+                let insertion = row[column - 1] + 1;
+                let deletion = previous_row[column] + 1;
+                let substitution = previous_row[column - 1] + usize::from(target[column - 1] != c);
+                row.push(insertion.min(deletion).min(substitution));
+            }
+
+            if node.value.is_some() && row[target.len()] <= distance {
+                results.push((prefix.clone(), row[target.len()]));
+            }
+            if row.iter().min().is_some_and(|&best| best <= distance) {
+                for (next, child) in &node.children {
+                    Self::search_within_distance_(child, *next, target, &row, distance, prefix, results);
+                }
+            }
+            prefix.pop();
+        }
+
+        // Glob style search: `?` matches one character, `*` any run of characters, `[abc]`,
+        // `[a-z]` and `[!abc]` a character from (or outside) a class. `\` escapes the next
+        // character. Only branches that can still match the pattern are visited.
+        pub fn search_pattern(&self, pattern: &str) -> Vec<String> {
+            let tokens = parse_pattern(pattern);
+            let mut words: Vec<String> = Vec::new();
+            let mut visited: HashSet<(*const WordEntry, usize)> = HashSet::new();
+            Self::search_pattern_(&self.words.root, &tokens, 0, &mut String::new(), &mut words, &mut visited);
+            words.sort();
+            words
+        }
+
+        fn search_pattern_(
+            node: &WordEntry,
+            tokens: &[PatternToken],
+            index: usize,
+            word: &mut String,
+            words: &mut Vec<String>,
+            visited: &mut HashSet<(*const WordEntry, usize)>,
+        ) {
+            // `*` can reach the same node at the same position along different paths
+            if !visited.insert((node as *const WordEntry, index)) {
+                return;
+            }
+            let token = match tokens.get(index) {
+                Some(token) => token,
+                None => {
+                    if node.value.is_some() {
+                        words.push(word.clone());
+                    }
+                    return;
+                }
+            };
+
+            let mut visit = |c: char, child: &WordEntry, next: usize, words: &mut Vec<String>| {
+                word.push(c);
+                Self::search_pattern_(child, tokens, next, word, words, visited);
+                word.pop();
+            };
+            match token {
+                PatternToken::Char(c) => {
+                    if let Some(child) = node.children.get(c) {
+                        visit(*c, child, index + 1, words);
+                    }
+                }
+                PatternToken::Star => {
+                    for (c, child) in &node.children {
+                        visit(*c, child, index, words);
+                    }
+                    Self::search_pattern_(node, tokens, index + 1, word, words, visited);
+                }
+                token => {
+                    for (c, child) in &node.children {
+                        if token.matches(*c) {
+                            visit(*c, child, index + 1, words);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Multi-pattern matcher over the words currently in the trie
+        pub fn to_automaton(&self) -> AhoCorasick {
+            AhoCorasick::new(self)
+        }
+
+        pub fn count_words(&self) -> i32 {
+            self.words.len() as i32
+        }
+
+        pub fn longest_common_prefix(&self, words: Vec<String>) -> String {
+            if words.is_empty() {
+                return String::new();
+            }
+
+            let mut trie = TrieNode::new();
+            for word in &words {
+                trie.insert(word);
+            }
+
+            let mut prefix = String::new();
+            let mut current = &trie.words.root;
+
+            while current.children.len() == 1 && current.value.is_none() {
+                let (c, next_node) = current.children.iter().next().unwrap();
+                prefix.push(*c);
+                current = next_node;
+            }
+
+            match self.contains(prefix.as_str()) {
+                true => prefix,
+                false => String::new(),
+            }
+        }
+
+        fn to_json(&self) -> String {
+            serde_json::to_string_pretty(&Self::to_json_(&self.words.root)).unwrap()
+        }
+
+        fn to_json_(node: &WordEntry) -> serde_json::Value {
+            let children = node
+                .children
+                .iter()
+                .map(|(c, child)| (c.to_string(), Self::to_json_(child)))
+                .collect::<serde_json::Map<String, serde_json::Value>>();
+            serde_json::json!({
+                "children": children,
+                "is_end_of_word": node.value.is_some(),
+                "prefix_count": node.count,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum MatchKind {
+        // Every occurrence of every pattern, including overlapping ones
+        Overlapping,
+        // Scanning left to right, the earliest starting match wins, the longest one among
+        // those starting at the same position. Reported matches never overlap.
+        LeftmostLongest,
+    }
+
+    // Byte offsets into the searched text, `&text[start..end] == pattern`
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Match {
+        pub pattern: String,
+        pub start: usize,
+        pub end: usize,
+    }
+
+    #[derive(Debug)]
+    struct AutomatonState {
+        children: HashMap<char, usize>,
+        // Longest proper suffix of this state that is also a state
+        failure: usize,
+        // Nearest state along the failure chain that ends a pattern
+        output_link: Option<usize>,
+        pattern: Option<usize>,
+    }
+
+    impl AutomatonState {
+        fn new() -> Self {
+            Self {
+                children: HashMap::new(),
+                failure: 0,
+                output_link: None,
+                pattern: None,
+            }
+        }
+    }
+
+    // Aho-Corasick automaton over the words of a trie, state 0 is the root
+    #[derive(Debug)]
+    pub struct AhoCorasick {
+        states: Vec<AutomatonState>,
+        patterns: Vec<String>,
+    }
+
+    impl AhoCorasick {
+        fn new(trie: &TrieNode) -> Self {
+            let mut automaton = Self {
+                states: vec![AutomatonState::new()],
+                patterns: Vec::new(),
+            };
+            automaton.add_states(&trie.words.root, 0, &mut String::new());
+            automaton.build_failure_links();
+            automaton
+        }
+
+        fn add_states(&mut self, node: &WordEntry, state: usize, word: &mut String) {
+            if node.value.is_some() {
+                self.states[state].pattern = Some(self.patterns.len());
+                self.patterns.push(word.clone());
+            }
+            for (c, child) in &node.children {
+                let next = self.states.len();
+                self.states.push(AutomatonState::new());
+                self.states[state].children.insert(*c, next);
+                word.push(*c);
+                self.add_states(child, next, word);
+                word.pop();
+            }
+        }
+
+        // Breadth first, so the failure target of every state is finished before its children
+        fn build_failure_links(&mut self) {
+            let mut queue = VecDeque::from([0]);
+            while let Some(state) = queue.pop_front() {
+                let children = self.states[state]
+                    .children
+                    .iter()
+                    .map(|(c, next)| (*c, *next))
+                    .collect::<Vec<(char, usize)>>();
+                for (c, next) in children {
+                    let failure = if state == 0 { 0 } else { self.transition(self.states[state].failure, c) };
+                    self.states[next].failure = failure;
+                    self.states[next].output_link = if self.states[failure].pattern.is_some() {
+                        Some(failure)
+                    } else {
+                        self.states[failure].output_link
+                    };
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        // Follows failure links until the character can be consumed, falling back to the root
+        fn transition(&self, mut state: usize, c: char) -> usize {
+            loop {
+                if let Some(&next) = self.states[state].children.get(&c) {
+                    return next;
+                }
+                if state == 0 {
+                    return 0;
+                }
+                state = self.states[state].failure;
+            }
+        }
+
+        pub fn find_all(&self, text: &str, kind: MatchKind) -> Vec<Match> {
+            let mut matches: Vec<(usize, usize, usize)> = Vec::new();
+            let mut state = 0;
+            for (index, c) in text.char_indices() {
+                state = self.transition(state, c);
+                let end = index + c.len_utf8();
+                let mut output = if self.states[state].pattern.is_some() {
+                    Some(state)
+                } else {
+                    self.states[state].output_link
+                };
+                while let Some(found) = output {
+                    let pattern = self.states[found].pattern.unwrap();
+                    matches.push((end - self.patterns[pattern].len(), end, pattern));
+                    output = self.states[found].output_link;
+                }
+            }
+
+            if kind == MatchKind::LeftmostLongest {
+                matches.sort_by(|first, second| first.0.cmp(&second.0).then(second.1.cmp(&first.1)));
+                let mut last_end = 0;
+                matches.retain(|&(start, end, _)| {
+                    // The empty pattern never advances the scan, so it is left out here
+                    let keep = start >= last_end && end > start;
+                    if keep {
+                        last_end = end;
+                    }
+                    keep
+                });
+            }
+
+            matches
+                .into_iter()
+                .map(|(start, end, pattern)| Match {
+                    pattern: self.patterns[pattern].clone(),
+                    start,
+                    end,
+                })
+                .collect()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum PatternToken {
+        Char(char),
+        Any,
+        Star,
+        Class { ranges: Vec<(char, char)>, negated: bool },
+    }
+
+    impl PatternToken {
+        fn matches(&self, c: char) -> bool {
+            match self {
+                PatternToken::Char(expected) => *expected == c,
+                PatternToken::Any | PatternToken::Star => true,
+                PatternToken::Class { ranges, negated } => {
+                    ranges.iter().any(|&(low, high)| low <= c && c <= high) != *negated
+                }
+            }
+        }
+    }
+
+    // An unterminated `[` is taken literally
+    fn parse_pattern(pattern: &str) -> Vec<PatternToken> {
+        let chars = pattern.chars().collect::<Vec<char>>();
+        let mut tokens = Vec::new();
+        let mut index = 0;
+        while index < chars.len() {
+            let token = match chars[index] {
+                '?' => PatternToken::Any,
+                // Consecutive stars match the same words as a single one
+                '*' if tokens.last() == Some(&PatternToken::Star) => {
+                    index += 1;
+                    continue;
+                }
+                '*' => PatternToken::Star,
+                '\\' if index + 1 < chars.len() => {
+                    index += 1;
+                    PatternToken::Char(chars[index])
+                }
+                '[' => match parse_class(&chars, index + 1) {
+                    Some((token, end)) => {
+                        index = end;
+                        token
+                    }
+                    None => PatternToken::Char('['),
+                },
+                c => PatternToken::Char(c),
+            };
+            tokens.push(token);
+            index += 1;
+        }
+        tokens
+    }
+
+    // Parses the class body starting at `start`, returning it with the index of the closing `]`
+    fn parse_class(chars: &[char], start: usize) -> Option<(PatternToken, usize)> {
+        let mut index = start;
+        let negated = matches!(chars.get(index), Some('!') | Some('^'));
+        if negated {
+            index += 1;
+        }
+        let mut ranges = Vec::new();
+        // A `]` right after the opening bracket is a member, not the end of the class
+        let body_start = index;
+        while index < chars.len() && (chars[index] != ']' || index == body_start) {
+            let low = chars[index];
+            if index + 2 < chars.len() && chars[index + 1] == '-' && chars[index + 2] != ']' {
+                ranges.push((low, chars[index + 2]));
+                index += 3;
+            } else {
+                ranges.push((low, low));
+                index += 1;
+            }
+        }
+        if index >= chars.len() {
+            return None;
+        }
+        Some((PatternToken::Class { ranges, negated }, index))
+    }
+
+    pub struct Words<'a> {
+        stack: Vec<(&'a WordEntry, String)>,
+    }
+
+    impl Iterator for Words<'_> {
+        type Item = String;
+
+        fn next(&mut self) -> Option<String> {
+            while let Some((node, word)) = self.stack.pop() {
+                let mut children = node.children.iter().collect::<Vec<(&char, &WordEntry)>>();
+                // Largest first, so the smallest child is popped next
+                children.sort_by(|first, second| second.0.cmp(first.0));
+                for (c, child) in children {
+                    let mut next = word.clone();
+                    next.push(*c);
+                    self.stack.push((child, next));
+                }
+                if node.value.is_some() {
+                    return Some(word);
+                }
+            }
+            None
+        }
+    }
+
+    // Compressed trie: every edge carries a whole run of characters, and nodes with a
+    // single child are merged into their parent edge
+    #[derive(Debug, Serialize)]
+    struct RadixNode {
+        // Sorted by the first character of the label, which is unique among siblings
+        edges: Vec<RadixEdge>,
+        is_end_of_word: bool,
+        prefix_count: usize,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct RadixEdge {
+        // Only the characters this edge adds below its parent, boxed so splits and merges
+        // leave no spare capacity behind
+        label: Box<str>,
+        node: RadixNode,
+    }
+
+    // Length in bytes of the common prefix, always on a character boundary of both strings
+    fn common_prefix_length(first: &str, second: &str) -> usize {
+        first
+            .char_indices()
+            .zip(second.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(first.len().min(second.len()), |((index, _), _)| index)
+    }
+
+    impl RadixNode {
+        pub fn new() -> Self {
+            Self {
+                edges: Vec::new(),
+                is_end_of_word: false,
+                prefix_count: 0,
+            }
+        }
+
+        fn find_edge(&self, c: char) -> Result<usize, usize> {
+            self.edges
+                .binary_search_by(|edge| edge.label.chars().next().unwrap().cmp(&c))
+        }
+
+        pub fn insert(&mut self, value: &str) {
+            if self.contains(value) {
+                return;
+            }
+            let mut current = self;
+            let mut rest = value;
+            current.prefix_count += 1;
+            while let Some(c) = rest.chars().next() {
+                let index = match current.find_edge(c) {
+                    Ok(index) => index,
+                    Err(index) => {
+                        let mut leaf = RadixNode::new();
+                        leaf.is_end_of_word = true;
+                        leaf.prefix_count = 1;
+                        current.edges.insert(index, RadixEdge { label: rest.into(), node: leaf });
+                        return;
+                    }
+                };
+
+                let edge = &mut current.edges[index];
+                let common = common_prefix_length(&edge.label, rest);
+                if common < edge.label.len() {
+                    // Split the edge, the shared part leads to a new node holding the old tail
+                    let tail: Box<str> = edge.label[common..].into();
+                    edge.label = edge.label[..common].into();
+                    let old_node = std::mem::replace(&mut edge.node, RadixNode::new());
+                    edge.node.prefix_count = old_node.prefix_count;
+                    edge.node.edges.push(RadixEdge { label: tail, node: old_node });
+                }
+                current = &mut current.edges[index].node;
+                current.prefix_count += 1;
+                rest = &rest[common..];
+            }
+            current.is_end_of_word = true;
+        }
+
+        pub fn len(&self) -> usize {
+            self.prefix_count
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.prefix_count == 0
+        }
+
+        pub fn count_with_prefix(&self, prefix: &str) -> usize {
+            let mut current = self;
+            let mut rest = prefix;
+            while let Some(c) = rest.chars().next() {
+                let edge = match current.find_edge(c) {
+                    Ok(index) => &current.edges[index],
+                    Err(_) => return 0,
+                };
+                if edge.label.starts_with(rest) {
+                    return edge.node.prefix_count;
+                }
+                if !rest.starts_with(&*edge.label) {
+                    return 0;
+                }
+                current = &edge.node;
+                rest = &rest[edge.label.len()..];
+            }
+            current.prefix_count
+        }
+
+        pub fn contains(&self, value: &str) -> bool {
+            let mut current = self;
+            let mut rest = value;
+            while let Some(c) = rest.chars().next() {
+                let edge = match current.find_edge(c) {
+                    Ok(index) => &current.edges[index],
+                    Err(_) => return false,
+                };
+                if !rest.starts_with(&*edge.label) {
+                    return false;
+                }
+                current = &edge.node;
+                rest = &rest[edge.label.len()..];
+            }
+            current.is_end_of_word
+        }
+
+        pub fn remove(&mut self, value: &str) -> bool {
+            self.remove_(value)
+        }
+
+        fn remove_(&mut self, rest: &str) -> bool {
+            let c = match rest.chars().next() {
+                Some(c) => c,
+                None => {
+                    let removed = self.is_end_of_word;
+                    if removed {
+                        self.is_end_of_word = false;
+                        self.prefix_count -= 1;
+                    }
+                    return removed;
+                }
+            };
+            let index = match self.find_edge(c) {
+                Ok(index) if rest.starts_with(&*self.edges[index].label) => index,
+                _ => return false,
+            };
+
+            let edge = &mut self.edges[index];
+            if !edge.node.remove_(&rest[edge.label.len()..]) {
+                return false;
+            }
+            self.prefix_count -= 1;
+            if !edge.node.is_end_of_word {
+                match edge.node.edges.len() {
+                    0 => {
+                        self.edges.remove(index);
+                    }
+                    // Merge the now redundant node into its parent edge
+                    1 => {
+                        let child = edge.node.edges.pop().unwrap();
+                        edge.label = [&*edge.label, &*child.label].concat().into();
+                        edge.node = child.node;
+                    }
+                    _ => {}
+                }
+            }
+            true
+        }
+
+        pub fn find_words(&self, prefix: String) -> Vec<String> {
+            let mut words: Vec<String> = Vec::new();
+            let mut current = self;
+            let mut word = String::new();
+            let mut rest = prefix.as_str();
+            while let Some(c) = rest.chars().next() {
+                let edge = match current.find_edge(c) {
+                    Ok(index) => &current.edges[index],
+                    Err(_) => return words,
+                };
+                if edge.label.starts_with(rest) {
+                    // The prefix ends inside this edge, complete it with the rest of the label
+                    word.push_str(&edge.label);
+                    current = &edge.node;
+                    break;
+                }
+                if !rest.starts_with(&*edge.label) {
+                    return words;
+                }
+                word.push_str(&edge.label);
+                current = &edge.node;
+                rest = &rest[edge.label.len()..];
+            }
+            current.find_words_(&mut word, &mut words);
+            words
+        }
+
+        fn find_words_(&self, word: &mut String, words: &mut Vec<String>) {
+            if self.is_end_of_word {
+                words.push(word.clone());
+            }
+            for edge in &self.edges {
+                word.push_str(&edge.label);
+                edge.node.find_words_(word, words);
+                word.truncate(word.len() - edge.label.len());
+            }
+        }
+
+        pub fn count_words(&self) -> i32 {
+            self.prefix_count as i32
+        }
+
+        pub fn longest_common_prefix(&self, words: Vec<String>) -> String {
+            if words.is_empty() {
+                return String::new();
+            }
+
+            let mut trie = RadixNode::new();
+            for word in &words {
+                trie.insert(word);
+            }
+
+            let mut prefix = String::new();
+            let mut current = &trie;
+            while current.edges.len() == 1 && !current.is_end_of_word {
+                prefix.push_str(&current.edges[0].label);
+                current = &current.edges[0].node;
+            }
+
+            match self.contains(prefix.as_str()) {
+                true => prefix,
+                false => String::new(),
+            }
+        }
+
+        fn to_json(&self) -> String {
+            serde_json::to_string_pretty(&self).unwrap()
+        }
+    }
+
+    // Trie ranking its words by weight. Every node caches the best `cache_size` completions
+    // of its subtree, so `top_k` for k up to that size only walks the prefix.
+    pub struct WeightedTrie {
+        root: WeightedTrieNode,
+        cache_size: usize,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct WeightedTrieNode {
+        children: HashMap<char, WeightedTrieNode>,
+        weight: Option<u32>,
+        // Best completions of the subtree, highest weight first and alphabetical among equals
+        top: Vec<(u32, String)>,
+    }
+
+    // Ordered so that the better completion is the greater one
+    fn rank(weight: u32, word: String) -> Reverse<(u32, Reverse<String>)> {
+        Reverse((weight, Reverse(word)))
+    }
+
+    // Selects the k best completions with a min-heap holding the current best k
+    fn best_completions<I: IntoIterator<Item = (u32, String)>>(candidates: I, k: usize) -> Vec<(u32, String)> {
+        let mut heap: Heap<Reverse<(u32, Reverse<String>)>> = Heap::new(k);
+        for (weight, word) in candidates {
+            if !heap.is_full() {
+                heap.insert(rank(weight, word));
+                continue;
+            }
+            let is_better = heap.max().is_some_and(|worst| rank(weight, word.clone()) < *worst);
+            if is_better {
+                heap.remove();
+                heap.insert(rank(weight, word));
+            }
+        }
+
+        let mut best = Vec::new();
+        while let Some(Reverse((weight, Reverse(word)))) = heap.remove() {
+            best.push((weight, word));
+        }
+        best.reverse();
+        best
+    }
+
+    impl WeightedTrieNode {
+        fn new() -> Self {
+            Self {
+                children: HashMap::new(),
+                weight: None,
+                top: Vec::new(),
+            }
+        }
+
+        // Weights only grow on insert, so the updated word either enters the cache or
+        // moves up, while every other cached entry stays valid
+        fn insert_(&mut self, chars: &[char], word: &str, weight: u32, cache_size: usize) -> u32 {
+            let total = match chars.split_first() {
+                Some((c, rest)) => self
+                    .children
+                    .entry(*c)
+                    .or_insert_with(WeightedTrieNode::new)
+                    .insert_(rest, word, weight, cache_size),
+                None => {
+                    let total = self.weight.unwrap_or(0).saturating_add(weight);
+                    self.weight = Some(total);
+                    total
+                }
+            };
+
+            self.top.retain(|(_, cached)| cached != word);
+            let candidates = self.top.drain(..).chain([(total, word.to_string())]).collect::<Vec<_>>();
+            self.top = best_completions(candidates, cache_size);
+            total
+        }
+
+        fn remove_(&mut self, chars: &[char], prefix: &mut String, cache_size: usize) -> Option<u32> {
+            let removed = match chars.split_first() {
+                Some((c, rest)) => {
+                    let child = self.children.get_mut(c)?;
+                    prefix.push(*c);
+                    let removed = child.remove_(rest, prefix, cache_size);
+                    prefix.pop();
+                    if child.children.is_empty() && child.weight.is_none() {
+                        self.children.remove(c);
+                    }
+                    removed
+                }
+                None => self.weight.take(),
+            };
+            if removed.is_some() {
+                self.refresh_top(prefix, cache_size);
+            }
+            removed
+        }
+
+        // Rebuilds the cache from the children, whose caches hold every candidate
+        fn refresh_top(&mut self, word: &str, cache_size: usize) {
+            let own = self.weight.map(|weight| (weight, word.to_string()));
+            let from_children = self.children.values().flat_map(|child| child.top.iter().cloned());
+            self.top = best_completions(own.into_iter().chain(from_children), cache_size);
+        }
+
+        fn collect_(&self, word: &mut String, completions: &mut Vec<(u32, String)>) {
+            if let Some(weight) = self.weight {
+                completions.push((weight, word.clone()));
+            }
+            for (c, child) in &self.children {
+                word.push(*c);
+                child.collect_(word, completions);
+                word.pop();
+            }
+        }
+    }
+
+    impl WeightedTrie {
+        pub fn new(cache_size: usize) -> Self {
+            Self {
+                root: WeightedTrieNode::new(),
+                cache_size,
+            }
+        }
+
+        // Adds the weight to the word, so inserting with weight 1 counts occurrences.
+        // Returns the new total weight.
+        pub fn insert(&mut self, value: &str, weight: u32) -> u32 {
+            let chars = value.chars().collect::<Vec<char>>();
+            self.root.insert_(&chars, value, weight, self.cache_size)
+        }
+
+        pub fn weight(&self, value: &str) -> Option<u32> {
+            self.find_last_node_of(value)?.weight
+        }
+
+        // Returns the weight the word had
+        pub fn remove(&mut self, value: &str) -> Option<u32> {
+            let chars = value.chars().collect::<Vec<char>>();
+            self.root.remove_(&chars, &mut String::new(), self.cache_size)
+        }
+
+        fn find_last_node_of(&self, prefix: &str) -> Option<&WeightedTrieNode> {
+            let mut current = &self.root;
+            for c in prefix.chars() {
+                current = current.children.get(&c)?;
+            }
+            Some(current)
+        }
+
+        // Highest weighted words starting with the prefix, alphabetical among equal weights
+        pub fn top_k(&self, prefix: &str, k: usize) -> Vec<(String, u32)> {
+            let node = match self.find_last_node_of(prefix) {
+                Some(node) => node,
+                None => return Vec::new(),
+            };
+            let best = if k <= self.cache_size {
+                node.top.iter().take(k).cloned().collect::<Vec<_>>()
+            } else {
+                let mut completions = Vec::new();
+                node.collect_(&mut prefix.to_string(), &mut completions);
+                best_completions(completions, k)
+            };
+            best.into_iter().map(|(weight, word)| (word, weight)).collect()
+        }
+    }
+
+    // Child table of a generic trie node, keyed by one symbol of the stored sequences
+    pub trait Children<K, N>: Default {
+        fn get(&self, symbol: &K) -> Option<&N>;
+        fn get_mut(&mut self, symbol: &K) -> Option<&mut N>;
+        fn get_or_insert_with<F: FnOnce() -> N>(&mut self, symbol: K, make: F) -> &mut N;
+        fn remove(&mut self, symbol: &K) -> Option<N>;
+        fn len(&self) -> usize;
+        fn iter(&self) -> Box<dyn Iterator<Item = (K, &N)> + '_>;
+
+        fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+    }
+
+    // Picks the child table used by every node of a trie
+    pub trait ChildStorage<K> {
+        type Children<N>: Children<K, N>;
+
+        // Whether the child table has a slot for the symbol
+        fn accepts(_symbol: &K) -> bool {
+            true
+        }
+    }
+
+    // A key with a symbol the child storage has no slot for, at `position` in the key
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct AlphabetError {
+        pub position: usize,
+    }
+
+    impl fmt::Display for AlphabetError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "symbol {} of the key is outside of the trie alphabet", self.position)
+        }
+    }
+
+    impl std::error::Error for AlphabetError {}
+
+    pub struct HashStorage;
+
+    // Children kept in a Vec sorted by symbol, compact and iterated in order
+    pub struct SortedStorage;
+
+    // One slot per symbol of a small fixed alphabet
+    pub struct ArrayStorage<A>(PhantomData<A>);
+
+    pub trait Alphabet<K> {
+        const SIZE: usize;
+        // Must be below SIZE for every symbol of the alphabet
+        fn index(symbol: &K) -> usize;
+        fn symbol(index: usize) -> K;
+    }
+
+    pub struct Lowercase;
+
+    impl Alphabet<char> for Lowercase {
+        const SIZE: usize = ALPHABET_SIZE;
+
+        fn index(symbol: &char) -> usize {
+            (*symbol as usize).wrapping_sub('a' as usize)
+        }
+
+        fn symbol(index: usize) -> char {
+            (b'a' + index as u8) as char
+        }
+    }
+
+    impl Alphabet<u8> for Lowercase {
+        const SIZE: usize = ALPHABET_SIZE;
+
+        fn index(symbol: &u8) -> usize {
+            (*symbol as usize).wrapping_sub(b'a' as usize)
+        }
+
+        fn symbol(index: usize) -> u8 {
+            b'a' + index as u8
+        }
+    }
+
+    pub struct Bytes;
+
+    impl Alphabet<u8> for Bytes {
+        const SIZE: usize = 256;
+
+        fn index(symbol: &u8) -> usize {
+            *symbol as usize
+        }
+
+        fn symbol(index: usize) -> u8 {
+            index as u8
+        }
+    }
+
+    impl<K: Hash + Eq + Clone, N> Children<K, N> for HashMap<K, N> {
+        fn get(&self, symbol: &K) -> Option<&N> {
+            HashMap::get(self, symbol)
+        }
+
+        fn get_mut(&mut self, symbol: &K) -> Option<&mut N> {
+            HashMap::get_mut(self, symbol)
+        }
+
+        fn get_or_insert_with<F: FnOnce() -> N>(&mut self, symbol: K, make: F) -> &mut N {
+            self.entry(symbol).or_insert_with(make)
+        }
+
+        fn remove(&mut self, symbol: &K) -> Option<N> {
+            HashMap::remove(self, symbol)
+        }
+
+        fn len(&self) -> usize {
+            HashMap::len(self)
+        }
+
+        fn iter(&self) -> Box<dyn Iterator<Item = (K, &N)> + '_> {
+            Box::new(HashMap::iter(self).map(|(symbol, child)| (symbol.clone(), child)))
+        }
+    }
+
+    impl<K: Hash + Eq + Clone> ChildStorage<K> for HashStorage {
+        type Children<N> = HashMap<K, N>;
+    }
+
+    pub struct SortedChildren<K, N> {
+        entries: Vec<(K, N)>,
+    }
+
+    impl<K, N> Default for SortedChildren<K, N> {
+        fn default() -> Self {
+            Self { entries: Vec::new() }
+        }
+    }
+
+    impl<K: Ord, N> SortedChildren<K, N> {
+        fn position(&self, symbol: &K) -> Result<usize, usize> {
+            self.entries.binary_search_by(|(key, _)| key.cmp(symbol))
+        }
+    }
+
+    impl<K: Ord + Clone, N> Children<K, N> for SortedChildren<K, N> {
+        fn get(&self, symbol: &K) -> Option<&N> {
+            let index = self.position(symbol).ok()?;
+            Some(&self.entries[index].1)
+        }
+
+        fn get_mut(&mut self, symbol: &K) -> Option<&mut N> {
+            let index = self.position(symbol).ok()?;
+            Some(&mut self.entries[index].1)
+        }
+
+        fn get_or_insert_with<F: FnOnce() -> N>(&mut self, symbol: K, make: F) -> &mut N {
+            let index = match self.position(&symbol) {
+                Ok(index) => index,
+                Err(index) => {
+                    self.entries.insert(index, (symbol, make()));
+                    index
+                }
+            };
+            &mut self.entries[index].1
+        }
+
+        fn remove(&mut self, symbol: &K) -> Option<N> {
+            let index = self.position(symbol).ok()?;
+            Some(self.entries.remove(index).1)
+        }
+
+        fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        fn iter(&self) -> Box<dyn Iterator<Item = (K, &N)> + '_> {
+            Box::new(self.entries.iter().map(|(symbol, child)| (symbol.clone(), child)))
+        }
+    }
+
+    impl<K: Ord + Clone> ChildStorage<K> for SortedStorage {
+        type Children<N> = SortedChildren<K, N>;
+    }
+
+    // Slots are only allocated once the first child is added, so leaves stay small
+    pub struct ArrayChildren<A, N> {
+        slots: Vec<Option<N>>,
+        len: usize,
+        alphabet: PhantomData<A>,
+    }
+
+    impl<A, N> Default for ArrayChildren<A, N> {
+        fn default() -> Self {
+            Self {
+                slots: Vec::new(),
+                len: 0,
+                alphabet: PhantomData,
+            }
+        }
+    }
+
+    impl<K, A: Alphabet<K>, N> Children<K, N> for ArrayChildren<A, N> {
+        fn get(&self, symbol: &K) -> Option<&N> {
+            self.slots.get(A::index(symbol))?.as_ref()
+        }
+
+        fn get_mut(&mut self, symbol: &K) -> Option<&mut N> {
+            self.slots.get_mut(A::index(symbol))?.as_mut()
+        }
+
+        fn get_or_insert_with<F: FnOnce() -> N>(&mut self, symbol: K, make: F) -> &mut N {
+            // `TrieMap` rejects keys with symbols outside of the alphabet before getting here
+            let index = A::index(&symbol);
+            assert!(index < A::SIZE, "symbol is outside of the trie alphabet");
+            if self.slots.is_empty() {
+                self.slots.resize_with(A::SIZE, || None);
+            }
+            if self.slots[index].is_none() {
+                self.len += 1;
+            }
+            self.slots[index].get_or_insert_with(make)
+        }
+
+        fn remove(&mut self, symbol: &K) -> Option<N> {
+            let removed = self.slots.get_mut(A::index(symbol))?.take();
+            if removed.is_some() {
+                self.len -= 1;
+            }
+            removed
+        }
+
+        fn len(&self) -> usize {
+            self.len
+        }
+
+        fn iter(&self) -> Box<dyn Iterator<Item = (K, &N)> + '_> {
+            Box::new(
+                self.slots
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, child)| child.as_ref().map(|child| (A::symbol(index), child))),
+            )
+        }
+    }
+
+    impl<K, A: Alphabet<K>> ChildStorage<K> for ArrayStorage<A> {
+        type Children<N> = ArrayChildren<A, N>;
+
+        fn accepts(symbol: &K) -> bool {
+            A::index(symbol) < A::SIZE
+        }
+    }
+
+    struct TrieEntry<K, V, S: ChildStorage<K>> {
+        children: S::Children<TrieEntry<K, V, S>>,
+        value: Option<V>,
+        // Number of values stored at or below this entry
+        count: usize,
+    }
+
+    impl<K, V, S: ChildStorage<K>> TrieEntry<K, V, S> {
+        fn new() -> Self {
+            Self {
+                children: Default::default(),
+                value: None,
+                count: 0,
+            }
+        }
+
+        fn insert_<I: Iterator<Item = K>>(&mut self, mut key: I, value: V) -> Option<V> {
+            let previous = match key.next() {
+                Some(symbol) => self.children.get_or_insert_with(symbol, TrieEntry::new).insert_(key, value),
+                None => self.value.replace(value),
+            };
+            if previous.is_none() {
+                self.count += 1;
+            }
+            previous
+        }
+
+        fn remove_(&mut self, key: &[K]) -> Option<V> {
+            let removed = match key.split_first() {
+                Some((symbol, rest)) => {
+                    let child = self.children.get_mut(symbol)?;
+                    let removed = child.remove_(rest);
+                    if child.count == 0 {
+                        self.children.remove(symbol);
+                    }
+                    removed
+                }
+                None => self.value.take(),
+            };
+            if removed.is_some() {
+                self.count -= 1;
+            }
+            removed
+        }
+
+        fn collect_<'a>(&'a self, prefix: &mut Vec<K>, entries: &mut Vec<(Vec<K>, &'a V)>)
+        where
+            K: Clone,
+        {
+            if let Some(value) = &self.value {
+                entries.push((prefix.clone(), value));
+            }
+            for (symbol, child) in self.children.iter() {
+                prefix.push(symbol);
+                child.collect_(prefix, entries);
+                prefix.pop();
+            }
+        }
+    }
+
+    // Map from sequences of symbols to values, `TrieMap<V>` is keyed by the chars of a string
+    pub struct TrieMap<V, K = char, S: ChildStorage<K> = HashStorage> {
+        root: TrieEntry<K, V, S>,
+    }
+
+    impl<V, K, S: ChildStorage<K>> TrieMap<V, K, S> {
+        pub fn new() -> Self {
+            Self { root: TrieEntry::new() }
+        }
+
+        pub fn len(&self) -> usize {
+            self.root.count
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.root.count == 0
+        }
+
+        // Returns the previous value stored under the key. Keys are checked against the
+        // alphabet of the child storage first, so a rejected key leaves the trie untouched.
+        pub fn insert<I: IntoIterator<Item = K>>(&mut self, key: I, value: V) -> Result<Option<V>, AlphabetError> {
+            let key = key.into_iter().collect::<Vec<K>>();
+            if let Some(position) = key.iter().position(|symbol| !S::accepts(symbol)) {
+                return Err(AlphabetError { position });
+            }
+            Ok(self.root.insert_(key.into_iter(), value))
+        }
+
+        fn find_last_node_of<I: IntoIterator<Item = K>>(&self, prefix: I) -> Option<&TrieEntry<K, V, S>> {
+            let mut current = &self.root;
+            for symbol in prefix {
+                current = current.children.get(&symbol)?;
+            }
+            Some(current)
+        }
+
+        pub fn get<I: IntoIterator<Item = K>>(&self, key: I) -> Option<&V> {
+            self.find_last_node_of(key)?.value.as_ref()
+        }
+
+        pub fn get_mut<I: IntoIterator<Item = K>>(&mut self, key: I) -> Option<&mut V> {
+            let mut current = &mut self.root;
+            for symbol in key {
+                current = current.children.get_mut(&symbol)?;
+            }
+            current.value.as_mut()
+        }
+
+        pub fn contains_key<I: IntoIterator<Item = K>>(&self, key: I) -> bool {
+            self.get(key).is_some()
+        }
+
+        pub fn starts_with<I: IntoIterator<Item = K>>(&self, prefix: I) -> bool {
+            self.find_last_node_of(prefix).is_some()
+        }
+
+        // Number of keys starting with the prefix, O(|prefix|)
+        pub fn count_with_prefix<I: IntoIterator<Item = K>>(&self, prefix: I) -> usize {
+            self.find_last_node_of(prefix).map_or(0, |node| node.count)
+        }
+
+        pub fn remove<I: IntoIterator<Item = K>>(&mut self, key: I) -> Option<V> {
+            let key = key.into_iter().collect::<Vec<K>>();
+            self.root.remove_(&key)
+        }
+
+        // Fails like `insert` for keys outside of the alphabet, so a vacant entry can always be filled
+        pub fn entry<I: IntoIterator<Item = K>>(&mut self, key: I) -> Result<Entry<'_, V, K, S>, AlphabetError>
+        where
+            K: Clone,
+        {
+            let key = key.into_iter().collect::<Vec<K>>();
+            if let Some(position) = key.iter().position(|symbol| !S::accepts(symbol)) {
+                return Err(AlphabetError { position });
+            }
+            if self.contains_key(key.iter().cloned()) {
+                Ok(Entry::Occupied(OccupiedEntry { map: self, key }))
+            } else {
+                Ok(Entry::Vacant(VacantEntry { map: self, key }))
+            }
+        }
+
+        // Entries come out in the iteration order of the child storage
+        pub fn entries_with_prefix<I: IntoIterator<Item = K>>(&self, prefix: I) -> Vec<(Vec<K>, &V)>
+        where
+            K: Clone,
+        {
+            let mut prefix = prefix.into_iter().collect::<Vec<K>>();
+            let mut entries = Vec::new();
+            if let Some(node) = self.find_last_node_of(prefix.clone()) {
+                node.collect_(&mut prefix, &mut entries);
+            }
+            entries
+        }
+
+        pub fn keys_with_prefix<I: IntoIterator<Item = K>>(&self, prefix: I) -> Vec<Vec<K>>
+        where
+            K: Clone,
+        {
+            self.entries_with_prefix(prefix)
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect()
+        }
+    }
+
+    impl<V, K, S: ChildStorage<K>> Default for TrieMap<V, K, S> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    pub enum Entry<'a, V, K, S: ChildStorage<K>> {
+        Occupied(OccupiedEntry<'a, V, K, S>),
+        Vacant(VacantEntry<'a, V, K, S>),
+    }
+
+    // Entries keep the key and walk the trie again when used, which keeps them O(|key|)
+    pub struct OccupiedEntry<'a, V, K, S: ChildStorage<K>> {
+        map: &'a mut TrieMap<V, K, S>,
+        key: Vec<K>,
+    }
+
+    pub struct VacantEntry<'a, V, K, S: ChildStorage<K>> {
+        map: &'a mut TrieMap<V, K, S>,
+        key: Vec<K>,
+    }
+
+    impl<'a, V, K: Clone, S: ChildStorage<K>> Entry<'a, V, K, S> {
+        pub fn key(&self) -> &[K] {
+            match self {
+                Entry::Occupied(entry) => &entry.key,
+                Entry::Vacant(entry) => &entry.key,
+            }
+        }
+
+        pub fn or_insert(self, value: V) -> &'a mut V {
+            self.or_insert_with(|| value)
+        }
+
+        pub fn or_insert_with<F: FnOnce() -> V>(self, make: F) -> &'a mut V {
+            match self {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(make()),
+            }
+        }
+
+        pub fn or_default(self) -> &'a mut V
+        where
+            V: Default,
+        {
+            self.or_insert_with(V::default)
+        }
+
+        pub fn and_modify<F: FnOnce(&mut V)>(mut self, modify: F) -> Self {
+            if let Entry::Occupied(entry) = &mut self {
+                modify(entry.get_mut());
+            }
+            self
+        }
+    }
+
+    impl<'a, V, K: Clone, S: ChildStorage<K>> OccupiedEntry<'a, V, K, S> {
+        pub fn get(&self) -> &V {
+            self.map.get(self.key.iter().cloned()).unwrap()
+        }
+
+        pub fn get_mut(&mut self) -> &mut V {
+            self.map.get_mut(self.key.iter().cloned()).unwrap()
+        }
+
+        pub fn into_mut(self) -> &'a mut V {
+            self.map.get_mut(self.key).unwrap()
+        }
+
+        pub fn insert(&mut self, value: V) -> V {
+            std::mem::replace(self.get_mut(), value)
+        }
+
+        pub fn remove(self) -> V {
+            self.map.remove(self.key).unwrap()
+        }
+    }
+
+    impl<'a, V, K: Clone, S: ChildStorage<K>> VacantEntry<'a, V, K, S> {
+        pub fn insert(self, value: V) -> &'a mut V {
+            // The key is vacant, so every entry along it gains one value
+            let mut current = &mut self.map.root;
+            current.count += 1;
+            for symbol in self.key {
+                current = current.children.get_or_insert_with(symbol, TrieEntry::new);
+                current.count += 1;
+            }
+            current.value.insert(value)
+        }
+    }
+
+    // Set of symbol sequences, e.g. `Trie<char>` for text,
+    // `Trie<u8, ArrayStorage<Bytes>>` for raw bytes or `Trie<u32, SortedStorage>` for tokens
+    pub struct Trie<K, S: ChildStorage<K> = HashStorage> {
+        map: TrieMap<(), K, S>,
+    }
+
+    impl<K, S: ChildStorage<K>> Trie<K, S> {
+        pub fn new() -> Self {
+            Self { map: TrieMap::new() }
+        }
+
+        pub fn len(&self) -> usize {
+            self.map.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.map.is_empty()
+        }
+
+        // Returns false when the key was already present
+        pub fn insert<I: IntoIterator<Item = K>>(&mut self, key: I) -> Result<bool, AlphabetError> {
+            Ok(self.map.insert(key, ())?.is_none())
+        }
+
+        pub fn contains<I: IntoIterator<Item = K>>(&self, key: I) -> bool {
+            self.map.contains_key(key)
+        }
+
+        pub fn starts_with<I: IntoIterator<Item = K>>(&self, prefix: I) -> bool {
+            self.map.starts_with(prefix)
+        }
+
+        // Returns whether the key was present
+        pub fn remove<I: IntoIterator<Item = K>>(&mut self, key: I) -> bool {
+            self.map.remove(key).is_some()
+        }
+
+        // Keys come out in the iteration order of the child storage
+        pub fn keys_with_prefix<I: IntoIterator<Item = K>>(&self, prefix: I) -> Vec<Vec<K>>
+        where
+            K: Clone,
+        {
+            self.map.keys_with_prefix(prefix)
+        }
+    }
+
+    impl<S: ChildStorage<char>> Trie<char, S> {
+        pub fn find_words(&self, prefix: &str) -> Vec<String> {
+            self.keys_with_prefix(prefix.chars())
+                .into_iter()
+                .map(|key| key.into_iter().collect())
+                .collect()
+        }
+    }
+
+    impl<K, S: ChildStorage<K>> Default for Trie<K, S> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    pub fn run() {
+        // let mut trie = TrieNode::new();
+        // trie.insert("car");
+        // trie.insert("care");
+        // trie.remove("care");
+        // // trie.traverse();
+        // println!("{}", trie.to_json());
+        let mut trie = TrieNode::new();
+        trie.insert("car");
+        trie.insert("careful");
+        trie.insert("card");
+        trie.insert("care");
+        trie.insert("egg");
+        println!("{:?}", trie.find_words("car".to_string()));
+        println!("{:?}", trie.iter_prefix("car").take(2).collect::<Vec<String>>());
+        println!("{:?}", trie.search_within_distance("cart", 1));
+        println!("{:?}", trie.search_pattern("c?r*"));
+        println!("{:?}", trie.search_pattern("car[de]"));
+        println!("{} {}", trie.len(), trie.count_with_prefix("car"));
+        println!("{} {}", trie.remove("card"), trie.remove("cart"));
+
+        let mut keywords = TrieNode::new();
+        for keyword in ["he", "she", "his", "hers"] {
+            keywords.insert(keyword);
+        }
+        let automaton = keywords.to_automaton();
+        println!("{:?}", automaton.find_all("ushers", MatchKind::Overlapping));
+        println!("{:?}", automaton.find_all("ushers", MatchKind::LeftmostLongest));
+        // println!("{:?}", trie.find_words("e".to_string()));
+        // println!("{:?}", trie.count_words());
+        println!(
+            "{:?}",
+            trie.longest_common_prefix(vec!["god".to_string(), "go".to_string()])
+        );
+
+        let mut words: Trie<char, ArrayStorage<Lowercase>> = Trie::new();
+        words.insert("car".chars()).unwrap();
+        words.insert("card".chars()).unwrap();
+        words.insert("care".chars()).unwrap();
+        println!("{:?}", words.find_words("car"));
+        println!("{:?} {}", words.insert("cart!".chars()), words.starts_with("cart".chars()));
+
+        let mut bytes: Trie<u8, ArrayStorage<Bytes>> = Trie::new();
+        bytes.insert("GET /index".bytes()).unwrap();
+        println!("{:?}", bytes.starts_with("GET".bytes()));
+
+        let mut tokens: Trie<u32, SortedStorage> = Trie::new();
+        tokens.insert([101, 7592, 102]).unwrap();
+        tokens.insert([101, 2088, 102]).unwrap();
+        tokens.remove([101, 2088, 102]);
+        println!("{:?} {}", tokens.keys_with_prefix([101]), tokens.len());
+
+        let mut documents: TrieMap<Vec<u32>> = TrieMap::new();
+        documents.insert("rust".chars(), vec![1]).unwrap();
+        documents.entry("rust".chars()).unwrap().or_default().push(4);
+        documents.entry("trie".chars()).unwrap().and_modify(|ids| ids.push(5)).or_insert(vec![2]);
+        *documents.entry("count".chars()).unwrap().or_default() = vec![3];
+        println!("{:?}", documents.get("rust".chars()));
+        println!("{:?}", documents.remove("trie".chars()));
+        println!("{:?} {}", documents.entries_with_prefix("".chars()), documents.len());
+
+        let mut radix = RadixNode::new();
+        radix.insert("romane");
+        radix.insert("romanus");
+        radix.insert("romulus");
+        radix.insert("rubens");
+        radix.insert("ruber");
+        radix.remove("romulus");
+        println!("{}", radix.to_json());
+        println!("{:?} {}", radix.find_words("rom".to_string()), radix.count_words());
+
+        let mut completions = WeightedTrie::new(3);
+        for (word, weight) in [("car", 5), ("card", 9), ("care", 2), ("careful", 7), ("cat", 4)] {
+            completions.insert(word, weight);
+        }
+        completions.insert("care", 1);
+        println!("{:?}", completions.top_k("car", 2));
+        completions.remove("card");
+        println!("{:?}", completions.top_k("ca", 5));
+    }
+}
+
+pub mod graphs {
+    use crate::collections::queue::Queue;
+    use crate::collections::stack::Stack;
+    use crate::part2::graph_io;
+    use crate::part2::graph_io::{GraphDocument, ParseError};
+    use std::collections::{HashMap, HashSet};
+    use std::fmt;
+
+    macro_rules! unwrap_or_return {
+        ($option:expr) => {
+            match $option {
+                Some(value) => value,
+                None => return,
+            }
+        };
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ParallelEdges {
+        Allow,
+        Deny,
+        // Keep a single edge between two nodes, holding the smallest weight seen
+        KeepMinWeight,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct EdgePolicy {
+        parallel_edges: ParallelEdges,
+        self_loops: bool,
+    }
+
+    impl EdgePolicy {
+        pub fn new() -> Self {
+            Self {
+                parallel_edges: ParallelEdges::Allow,
+                self_loops: true,
+            }
+        }
+
+        pub fn parallel_edges(mut self, parallel_edges: ParallelEdges) -> Self {
+            self.parallel_edges = parallel_edges;
+            self
+        }
+
+        pub fn self_loops(mut self, allow: bool) -> Self {
+            self.self_loops = allow;
+            self
+        }
+
+        pub fn get_parallel_edges(&self) -> ParallelEdges {
+            self.parallel_edges
+        }
+
+        pub fn allows_self_loops(&self) -> bool {
+            self.self_loops
+        }
+    }
+
+    impl Default for EdgePolicy {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum GraphError {
+        NodeNotFound(String),
+        SelfLoop(String),
+        ParallelEdge(String, String),
+        NegativeCapacity(String, String, i32),
+        SameSourceAndSink(String),
+    }
+
+    impl fmt::Display for GraphError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                GraphError::NodeNotFound(label) => write!(f, "node {} not found", label),
+                GraphError::SelfLoop(label) => write!(f, "self loop on {} is not allowed", label),
+                GraphError::ParallelEdge(from, to) => {
+                    write!(f, "edge {} -> {} already exists", from, to)
+                }
+                GraphError::NegativeCapacity(from, to, capacity) => {
+                    write!(f, "edge {} -> {} has negative capacity {}", from, to, capacity)
+                }
+                GraphError::SameSourceAndSink(label) => {
+                    write!(f, "{} is both the source and the sink", label)
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for GraphError {}
+
+    #[derive(Debug, Eq, Hash, PartialEq, Clone)]
+    struct GraphNode {
+        label: String,
+    }
+    impl GraphNode {
+        pub fn new(label: String) -> Self {
+            Self { label }
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Graph {
+        node: HashMap<String, GraphNode>,
+        adjacency_list: HashMap<String, Vec<GraphNode>>,
+        policy: EdgePolicy,
+    }
+
+    impl Graph {
+        pub fn new() -> Self {
+            Self::with_policy(EdgePolicy::new())
+        }
+
+        pub fn with_policy(policy: EdgePolicy) -> Self {
+            Self {
+                node: HashMap::new(),
+                adjacency_list: HashMap::new(),
+                policy,
+            }
+        }
+
+        pub fn add_node(&mut self, label: String) {
+            let node = GraphNode::new(label.to_owned());
+            self.node.entry(label.to_owned()).or_insert(node);
+            self.adjacency_list
+                .entry(label.to_owned())
+                .or_insert(Vec::new());
+        }
+
+        pub fn remove_node(&mut self, label: String) {
+            let node = unwrap_or_return!(self.node.get(&label).cloned());
+            let keys = self.adjacency_list.keys().cloned().collect::<Vec<String>>();
+            for key in keys {
+                self.adjacency_list.get_mut(&key).and_then(|list| {
+                    let index = list.iter().position(|n| n.eq(&node));
+                    match index {
+                        Some(index) => {
+                            list.remove(index);
+                            Some(())
+                        }
+                        None => None,
+                    }
+                });
+                self.adjacency_list.remove(&label);
+                self.node.remove(&label);
+            }
+        }
+
+        pub fn add_edge(&mut self, from: String, to: String) -> Result<(), GraphError> {
+            let to_node = match self.node.get(&to).cloned() {
+                Some(node) => node,
+                None => return Err(GraphError::NodeNotFound(to)),
+            };
+            if from == to && !self.policy.allows_self_loops() {
+                return Err(GraphError::SelfLoop(from));
+            }
+            let policy = self.policy;
+            let list = match self.adjacency_list.get_mut(&from) {
+                Some(list) => list,
+                None => return Err(GraphError::NodeNotFound(from)),
+            };
+            if list.contains(&to_node) {
+                match policy.get_parallel_edges() {
+                    ParallelEdges::Allow => {}
+                    ParallelEdges::Deny => return Err(GraphError::ParallelEdge(from, to)),
+                    // Unweighted edges are identical, so the existing one already is the minimum
+                    ParallelEdges::KeepMinWeight => return Ok(()),
+                }
+            }
+            list.push(to_node);
+            Ok(())
+        }
+
+        pub fn remove_edge(&mut self, from: String, to: String) {
+            let to_node = unwrap_or_return!(self.node.get(&to).cloned());
             self.adjacency_list.get_mut(&from).and_then(|x| {
                 match x.iter().position(|n| n.eq(&to_node)) {
                     Some(index) => {
                         x.remove(index);
                         Some(())
                     }
-                    None => None,
+                    None => None,
+                }
+            });
+        }
+
+        pub fn depth_first_traversal_recursive(&self, root: String) {
+            let mut visited: HashSet<GraphNode> = HashSet::new();
+            let root = unwrap_or_return!(self.node.get(&root).cloned());
+            self.depth_first_traversal_recursive_(root, &mut visited);
+        }
+
+        fn depth_first_traversal_recursive_(
+            &self,
+            root: GraphNode,
+            visited: &mut HashSet<GraphNode>,
+        ) {
+            println!("{:?}", root);
+            visited.insert(root.clone());
+
+            for node in self.adjacency_list.get(&root.label).cloned().unwrap() {
+                if !visited.contains(&node) {
+                    self.depth_first_traversal_recursive_(node, visited);
+                }
+            }
+        }
+
+        pub fn depth_first_traversal_iterative(&mut self, root: String) {
+            let node = unwrap_or_return!(self.node.get(&root).cloned());
+            let mut visited: HashSet<GraphNode> = HashSet::new();
+            let mut stack: Stack<GraphNode> = Stack::new();
+            stack.push(node.clone());
+            while !stack.is_empty() {
+                let current = unwrap_or_return!(stack.pop());
+                if visited.contains(&current) {
+                    continue;
+                }
+                println!("{:?}", current);
+                visited.insert(current.clone());
+                for neighbour in self.get_adjacent_nodes(&current).unwrap() {
+                    if !visited.contains(&neighbour) {
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        pub fn breadth_first_traversal(&mut self, root: String) {
+            let node = unwrap_or_return!(self.node.get(&root).cloned());
+            let mut visited: HashSet<GraphNode> = HashSet::new();
+            let mut queue: Queue<GraphNode> = Queue::new();
+            queue.enqueue(node.clone());
+            while let Some(current) = queue.dequeue() {
+                if visited.contains(&current) {
+                    continue;
+                }
+                println!("{:?}", current);
+                visited.insert(current.clone());
+                for neighbour in self.get_adjacent_nodes(&current).unwrap() {
+                    if !visited.contains(&neighbour) {
+                        queue.enqueue(neighbour);
+                    }
+                }
+            }
+        }
+
+        fn get_adjacent_nodes(&self, node: &GraphNode) -> Option<Vec<GraphNode>> {
+            self.adjacency_list.get(&node.label).cloned()
+        }
+
+        pub fn topological_sort(&mut self) -> Vec<String> {
+            let mut visited: HashSet<GraphNode> = HashSet::new();
+            let mut stack: Stack<GraphNode> = Stack::new();
+            let current = self.node.clone();
+            for node in current.values() {
+                self.topological_sort_(node.clone(), &mut visited, &mut stack);
+            }
+
+            let mut sorted: Vec<String> = Vec::new();
+            // println!("{:?}", stack);
+
+            while !stack.is_empty() {
+                sorted.push(stack.pop().unwrap().label)
+            }
+
+            sorted
+        }
+
+        fn topological_sort_(&mut self, node: GraphNode, visited: &mut HashSet<GraphNode>, stack: &mut Stack<GraphNode>) {
+            if visited.contains(&node) {
+                return;
+            }
+
+            // [P, B, X, A]
+            visited.insert(node.clone());
+            for neighbour in self.get_adjacent_nodes(&node).clone().unwrap() {
+                // println!("neighbour {:?}", neighbour);
+                // P
+                self.topological_sort_(neighbour, visited, stack);
+            }
+
+            // [P, B, A, X]
+            stack.push(node);
+            // println!("stack result {:?}", stack);
+        }
+
+        pub fn has_cycle(&mut self) -> bool {
+            let mut all: HashSet<GraphNode> = HashSet::new();
+            self.node.values().for_each(|node| {
+                all.insert(node.clone());
+            });
+
+            let mut visiting: HashSet<GraphNode> = HashSet::new();
+            let mut visited: HashSet<GraphNode> = HashSet::new();
+            println!("{:?}", all);
+
+            while !all.is_empty() {
+                let current = all.iter().next().unwrap().clone();
+                if self.has_cycle_(current, &mut all, &mut visiting, &mut visited) {
+                    return true
+                }
+            }
+            false
+        }
+
+        fn has_cycle_(&self, root: GraphNode, all: &mut HashSet<GraphNode>, visiting: &mut HashSet<GraphNode>, visited: &mut HashSet<GraphNode>) -> bool {
+            all.remove(&root);
+            visiting.insert(root.clone());
+
+            for neighbour in self.get_adjacent_nodes(&root).unwrap() {
+                println!("neighbour {:?}", neighbour);
+                if visited.contains(&neighbour) {
+                    continue
+                }
+
+                if visiting.contains(&neighbour) {
+                    return true
+                }
+
+                if self.has_cycle_(neighbour, all, visiting, visited) {
+                    return true
+                }
+            }
+
+            visiting.remove(&root);
+            visited.insert(root.clone());
+            return false
+        }
+
+        pub fn nodes(&self) -> Vec<String> {
+            let mut nodes = self.node.keys().cloned().collect::<Vec<String>>();
+            nodes.sort();
+            nodes
+        }
+
+        pub fn edges(&self) -> Vec<(String, String)> {
+            let mut edges = Vec::new();
+            for from in self.nodes() {
+                let mut targets = self.adjacency_list[&from]
+                    .iter()
+                    .map(|node| node.label.clone())
+                    .collect::<Vec<String>>();
+                targets.sort();
+                for to in targets {
+                    edges.push((from.clone(), to));
+                }
+            }
+            edges
+        }
+
+        fn to_document(&self) -> GraphDocument {
+            let mut document = GraphDocument::new(true);
+            for node in self.nodes() {
+                document.add_node(node);
+            }
+            for (from, to) in self.edges() {
+                document.add_edge(from, to, None);
+            }
+            document
+        }
+
+        fn from_document(document: GraphDocument) -> Self {
+            let mut graph = Graph::new();
+            for node in document.nodes {
+                graph.add_node(node);
+            }
+            for edge in document.edges {
+                graph
+                    .add_edge(edge.from, edge.to)
+                    .expect("document nodes are added before its edges");
+            }
+            graph
+        }
+
+        pub fn to_dot(&self) -> String {
+            graph_io::to_dot(&self.to_document())
+        }
+
+        pub fn from_dot(input: &str) -> Result<Self, ParseError> {
+            graph_io::parse_dot(input, true, false).map(Self::from_document)
+        }
+
+        pub fn to_edge_list(&self) -> String {
+            graph_io::to_edge_list(&self.to_document())
+        }
+
+        pub fn from_edge_list(input: &str) -> Result<Self, ParseError> {
+            graph_io::parse_edge_list(input, true, false).map(Self::from_document)
+        }
+
+        pub fn to_adjacency_json(&self) -> String {
+            graph_io::to_adjacency_json(&self.to_document(), false)
+        }
+
+        pub fn from_adjacency_json(input: &str) -> Result<Self, ParseError> {
+            graph_io::parse_adjacency_json(input, true, false).map(Self::from_document)
+        }
+
+        pub fn print(&mut self) {
+            for (key, value) in &self.adjacency_list {
+                let targets = self.adjacency_list.get(key).unwrap();
+                if !targets.is_empty() {
+                    let label = targets
+                        .iter()
+                        .map(|node| node.label.clone())
+                        .collect::<Vec<String>>();
+                    println!("{} is connected to {:?}", key, label);
+                }
+            }
+        }
+    }
+
+    pub fn run() {
+        // let mut graph = Graph::new();
+        // graph.add_node("kevin".to_string());
+        // graph.add_node("vina".to_string());
+        // graph.add_node("becca".to_string());
+        // graph.add_edge("kevin".to_string(), "vina".to_string());
+        // graph.add_edge("kevin".to_string(), "becca".to_string());
+
+        // graph.remove_edge("kevin".to_string(), "d".to_string());
+        // graph.remove_node("kevin".to_string());
+        // graph.add_edge("vina".to_string(), "becca".to_string());
+        // graph.print();
+        // println!("{:?}", graph);
+
+        // let mut graph = Graph::new();
+        // graph.add_node("A".to_string());
+        // graph.add_node("B".to_string());
+        // graph.add_node("C".to_string());
+        // graph.add_node("D".to_string());
+        // graph.add_edge("A".to_string(), "B".to_string());
+        // graph.add_edge("B".to_string(), "D".to_string());
+        // graph.add_edge("D".to_string(), "C".to_string());
+        // graph.add_edge("A".to_string(), "C".to_string());
+        // graph.print();
+        // graph.breadth_first_traversal("D".to_string());
+
+        // let mut graph = Graph::new();
+        // graph.add_node("X".to_string());
+        // graph.add_node("P".to_string());
+        // graph.add_node("A".to_string());
+        // graph.add_node("B".to_string());
+        // graph.add_edge("X".to_string(), "A".to_string());
+        // graph.add_edge("X".to_string(), "B".to_string());
+        // graph.add_edge("A".to_string(), "P".to_string());
+        // graph.add_edge("B".to_string(), "P".to_string());
+        // println!("{:?}", graph.topological_sort());
+        // graph.print();
+
+        let mut graph = Graph::new();
+        graph.add_node("A".to_string());
+        graph.add_node("B".to_string());
+        graph.add_node("C".to_string());
+        graph.add_edge("A".to_string(), "B".to_string()).unwrap();
+        graph.add_edge("B".to_string(), "C".to_string()).unwrap();
+        graph.add_edge("C".to_string(), "A".to_string()).unwrap();
+        println!("{:?}", graph.has_cycle());
+
+        let policy = EdgePolicy::new()
+            .parallel_edges(ParallelEdges::Deny)
+            .self_loops(false);
+        let mut graph = Graph::with_policy(policy);
+        graph.add_node("A".to_string());
+        graph.add_node("B".to_string());
+        graph.add_edge("A".to_string(), "B".to_string()).unwrap();
+        println!("{:?}", graph.add_edge("A".to_string(), "B".to_string()));
+        println!("{:?}", graph.add_edge("A".to_string(), "A".to_string()));
+
+        println!("{}", graph.to_dot());
+        println!("{}", graph.to_edge_list());
+        println!("{}", graph.to_adjacency_json());
+        match Graph::from_dot("digraph {\n    A -> B;\n    B -> ;\n}") {
+            Ok(graph) => println!("{}", graph.to_edge_list()),
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+pub mod ungraphs {
+    use std::cmp::{min, Reverse};
+    use std::collections::{HashMap, HashSet, VecDeque};
+    use crate::part2::assignment;
+    use crate::part2::graph_io;
+    use crate::part2::graph_io::{GraphDocument, ParseError};
+    use crate::part2::graphs;
+    use crate::part2::heaps;
+    use crate::part2::graphs::{EdgePolicy, GraphError, ParallelEdges};
+
+    macro_rules! unwrap_or_return {
+        ($option:expr) => {
+            match $option {
+                Some(value) => value,
+                None => return,
+            }
+        };
+    }
+
+    #[derive(Debug)]
+    struct Path {
+        nodes: Vec<String>,
+    }
+
+    impl Path {
+        pub fn new() -> Self {
+            Self {
+                nodes: Vec::new(),
+            }
+        }
+
+        pub fn add_node(&mut self, node: String) {
+            self.nodes.push(node);
+        }
+
+        pub fn to_string(&self) -> String {
+            self.nodes.join(" -> ")
+        }
+    }
+
+    #[derive(Debug)]
+    struct PriorityQueue<T: Ord> {
+        elements: Vec<T>,
+    }
+
+    impl<T: Ord> PriorityQueue<T> {
+        pub fn new() -> Self {
+            Self {
+                elements: Vec::new(),
+            }
+        }
+
+        pub fn enqueue(&mut self, item: T) {
+            self.elements.push(item);
+            // Sort in ascending order
+            self.elements.sort();
+        }
+
+        pub fn dequeue(&mut self) -> Option<T> {
+            self.elements.pop()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.elements.is_empty()
+        }
+
+        pub fn peek(&self) -> Option<&T> {
+            self.elements.last()
+        }
+
+        pub fn count(&self) -> usize {
+            self.elements.len()
+        }
+
+        pub fn get_all(&self) -> &Vec<T> {
+            &self.elements
+        }
+    }
+
+    #[derive(Debug, Eq, Hash, PartialEq, Clone, Ord, PartialOrd)]
+    struct GraphNode {
+        label: String,
+        edges: Vec<Edge>
+    }
+    impl GraphNode {
+        pub fn new(label: String) -> Self {
+            Self { label, edges: Vec::new() }
+        }
+
+        pub fn add_edge(&mut self, to: GraphNode, weight: i32) {
+            self.edges.push(Edge::new(self.clone(), to, weight));
+        }
+
+        pub fn get_edges(&self) -> Vec<Edge> {
+            self.edges.clone()
+        }
+
+        fn has_edge_to(&self, to: &str) -> bool {
+            self.edges.iter().any(|edge| edge.to.label == to)
+        }
+
+        fn keep_min_weight(&mut self, to: &str, weight: i32) {
+            self.edges
+                .iter_mut()
+                .filter(|edge| edge.to.label == to && edge.weight > weight)
+                .for_each(|edge| edge.weight = weight);
+        }
+    }
+
+    #[derive(Debug, Eq, Hash, PartialEq, Clone, Ord, PartialOrd)]
+    struct Edge {
+        from: GraphNode,
+        to: GraphNode,
+        weight: i32
+    }
+
+    impl Edge {
+        pub fn new(from: GraphNode, to: GraphNode, weight: i32) -> Self {
+            Self {
+                from,
+                to,
+                weight
+            }
+        }
+
+        pub fn to_string(&self) -> String {
+            format!("{} -> {} ({})", self.from.label, self.to.label, self.weight)
+        }
+    }
+
+    #[derive(Debug)]
+    struct WeightedGraphs {
+        nodes: HashMap<String, GraphNode>,
+        policy: EdgePolicy,
+    }
+
+    #[derive(Debug, Hash, Ord, Eq, PartialEq, PartialOrd)]
+    struct NodeEntry {
+        node: GraphNode,
+        priority: i32
+    }
+
+    impl NodeEntry {
+        pub fn new(node: GraphNode, priority: i32) -> Self {
+            Self {
+                node,
+                priority
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Bipartition {
+        Colouring(Vec<String>, Vec<String>),
+        // Nodes along a cycle of odd length, the last one connects back to the first
+        OddCycle(Vec<String>),
+    }
+
+    impl WeightedGraphs {
+        pub fn new() -> Self {
+            Self::with_policy(EdgePolicy::new())
+        }
+
+        pub fn with_policy(policy: EdgePolicy) -> Self {
+            Self {
+                nodes: HashMap::new(),
+                policy,
+            }
+        }
+
+        pub fn to_string(&self) -> String {
+            let mut result = String::new();
+            self.nodes.values().into_iter().for_each(
+                |node| {
+                    let edges = node.get_edges().iter().map(|edge| edge.to_string()).collect::<Vec<String>>().join(", ");
+                    result.push_str(&format!("{} -> [{}]\n", node.label, edges));
+                }
+            );
+            result
+        }
+
+        pub fn add_node(&mut self, label: String) {
+            self.nodes.entry(label.to_owned()).or_insert(GraphNode::new(label));
+        }
+
+        pub fn add_edge(&mut self, from: String, to: String, weight: i32) -> Result<(), GraphError> {
+            let to_node = match self.nodes.get(&to).cloned() {
+                Some(node) => node,
+                None => return Err(GraphError::NodeNotFound(to)),
+            };
+            let from_node = match self.nodes.get(&from).cloned() {
+                Some(node) => node,
+                None => return Err(GraphError::NodeNotFound(from)),
+            };
+            if from == to && !self.policy.allows_self_loops() {
+                return Err(GraphError::SelfLoop(from));
+            }
+
+            if from_node.has_edge_to(&to) {
+                match self.policy.get_parallel_edges() {
+                    ParallelEdges::Allow => {}
+                    ParallelEdges::Deny => return Err(GraphError::ParallelEdge(from, to)),
+                    ParallelEdges::KeepMinWeight => {
+                        if let Some(from_node) = self.nodes.get_mut(&from) {
+                            from_node.keep_min_weight(&to, weight);
+                        }
+                        if from != to {
+                            if let Some(to_node) = self.nodes.get_mut(&to) {
+                                to_node.keep_min_weight(&from, weight);
+                            }
+                        }
+                        return Ok(());
+                    }
+                }
+            }
+
+            // A self loop is a single adjacency entry, every other edge is stored on both ends
+            if from != to {
+                if let Some(to) = self.nodes.get_mut(&to) {
+                    to.add_edge(from_node, weight);
+                }
+            }
+            if let Some(from) = self.nodes.get_mut(&from) {
+                from.add_edge(to_node, weight);
+            }
+            Ok(())
+        }
+
+
+        pub fn get_shortest_path(&self, from: String, to: String) -> Path {
+            let from_node = if let Some(from_node) = self.nodes.get(&from).cloned() {
+                from_node
+            } else {
+                return Path::new()
+            };
+            let to_node = if let Some(to_node) = self.nodes.get(&to).cloned() {
+                to_node
+            } else {
+                return Path::new()
+            };
+            println!("from {} to {}", from_node.label, to_node.label);
+
+            let mut distance: HashMap<GraphNode, i32> = HashMap::new();
+            for node in self.nodes.values() {
+                distance.insert(node.clone(), i32::MAX);
+            }
+            distance.insert(from_node.clone(), 0);
+            distance.iter().for_each(|(key, value)| {
+                println!("distance {} -> {}", key.label, value);
+            });
+
+            let mut previous_nodes: HashMap<GraphNode, GraphNode> = HashMap::new();
+            let mut visited: HashSet<GraphNode> = HashSet::new();
+            let mut queue = priority_queue::PriorityQueue::new();
+            queue.push(NodeEntry::new(from_node.clone(), 0), Reverse(0));
+            println!("Added to queue {}", from_node.label);
+            println!();
+
+            while let Some((NodeEntry { node: current, .. }, _)) = queue.pop() {
+                visited.insert(current.clone());
+                println!("Visited [{}]", visited.iter().map(|x|x.label.clone()).collect::<Vec<String>>().join(", "));
+
+                println!("Processing Node {} == {}", current.label, current.get_edges().iter()
+                    .map(|x|x.to_string()).collect::<Vec<String>>().join(", "));
+                for edge in current.get_edges() {
+                    println!("processing Edge {} > {} with weight {}", edge.from.label,
+                             edge.to.label, edge.weight);
+                    let neighbor = self.nodes.get(&edge.to.label).cloned().unwrap();
+                    if visited.contains(&neighbor) {
+                        println!("Already visited {}", neighbor.label);
+                        continue;
+                    }
+
+                    let new_distance = distance[&current] + edge.weight;
+                    println!("New distance for {} is {}", neighbor.label, new_distance);
+                    if new_distance < distance[&neighbor] {
+                        println!("The new distance is less than the current distance {} < {}", new_distance, distance[&neighbor]);
+                        distance.insert(neighbor.clone(), new_distance);
+                        previous_nodes.insert(neighbor.clone(), current.clone());
+                        println!("Add to queue {} with distance {}", neighbor.label, new_distance);
+                        queue.push(NodeEntry::new(neighbor.clone(), new_distance), Reverse(new_distance));
+                    }
+                    println!();
+                }
+            }
+
+            previous_nodes.iter().for_each(|(key, value)| {
+                println!("{} -> {}", key.label, value.label);
+            });
+
+            self.build_path(to_node, previous_nodes)
+        }
+
+        // Dijkstra over any `heaps::PriorityQueue`, so the heap variants can be compared on
+        // the same graph. Outdated queue entries are skipped when popped instead of updated.
+        pub fn shortest_distances<Q>(&self, from: &str, mut queue: Q) -> HashMap<String, i32>
+        where
+            Q: heaps::PriorityQueue<Reverse<(i32, String)>>,
+        {
+            let mut distance: HashMap<String, i32> = HashMap::new();
+            if !self.nodes.contains_key(from) {
+                return distance;
+            }
+            queue.push(Reverse((0, from.to_string())));
+            while let Some(Reverse((current_distance, label))) = queue.pop() {
+                if distance.contains_key(&label) {
+                    continue;
                 }
+                for edge in &self.nodes[&label].edges {
+                    if !distance.contains_key(&edge.to.label) {
+                        queue.push(Reverse((current_distance + edge.weight, edge.to.label.clone())));
+                    }
+                }
+                distance.insert(label, current_distance);
+            }
+            distance
+        }
+
+        fn build_path(&self, to_node: GraphNode, previous_nodes: HashMap<GraphNode, GraphNode>) -> Path {
+            let mut path = Path::new();
+            let mut current_label = to_node.clone();
+
+            while let Some(prev_label) = previous_nodes.get(&current_label) {
+                path.add_node(current_label.label.clone());
+                current_label = prev_label.clone();
+            }
+            path.add_node(current_label.label);
+            path.nodes.reverse();
+            path
+        }
+
+        pub fn get_edges(&self, node: String) {
+            let result = unwrap_or_return!(self.nodes.get(&node).cloned());
+            println!("Edges for node {}", result.label);
+            result.get_edges().iter().for_each(|edge| {
+                println!("{}", edge.to_string());
             });
         }
 
-        pub fn depth_first_traversal_recursive(&self, root: String) {
-            let mut visited: HashSet<GraphNode> = HashSet::new();
-            let root = unwrap_or_return!(self.node.get(&root).cloned());
-            self.depth_first_traversal_recursive_(root, &mut visited);
+        pub fn has_cycle(&self) -> bool {
+            let mut visited: HashSet<String> = HashSet::new();
+            for node in self.nodes.values() {
+                println!("Checking node {}", node.label);
+                if !visited.contains(&node.label) &&
+                    self.has_cycle_(node.clone(), &mut visited, &GraphNode::new("".to_string())) {
+                    return true
+                }
+            }
+            false
         }
 
-        fn depth_first_traversal_recursive_(
-            &self,
-            root: GraphNode,
-            visited: &mut HashSet<GraphNode>,
-        ) {
-            println!("{:?}", root);
-            visited.insert(root.clone());
+        fn has_cycle_(&self, node: GraphNode, visited: &mut HashSet<String>, parent: &GraphNode) -> bool {
+            visited.insert(node.label.clone());
+            for edge in node.get_edges() {
+                println!("Checking edge {} -> {}", edge.from.label, edge.to.label);
+                if edge.to == *parent {
+                    continue
+                }
 
-            for node in self.adjacency_list.get(&root.label).cloned().unwrap() {
-                if !visited.contains(&node) {
-                    self.depth_first_traversal_recursive_(node, visited);
+                if visited.contains(&edge.to.label) ||
+                    self.has_cycle_(edge.to.clone(), visited, &node) {
+                    return true
                 }
             }
+            return false
         }
 
-        pub fn depth_first_traversal_iterative(&mut self, root: String) {
-            let node = unwrap_or_return!(self.node.get(&root).cloned());
-            let mut visited: HashSet<GraphNode> = HashSet::new();
-            let mut stack: Stack<GraphNode> = Stack::new();
-            stack.push(node.clone());
-            while !stack.is_empty() {
-                let current = unwrap_or_return!(stack.pop());
-                if visited.contains(&current) {
-                    continue;
+        pub fn get_minimum_spanning_tree(&self) -> Self {
+            let mut tree = WeightedGraphs::with_policy(self.policy);
+            if self.nodes.is_empty() {
+                return tree
+            }
+            let start_node = self.nodes.values().next().unwrap().clone();
+            let mut edges = priority_queue::PriorityQueue::new();
+            start_node.get_edges().iter().for_each(|edge| {
+                edges.push(edge.clone(), Reverse(edge.weight));
+            });
+
+            if edges.is_empty() {
+                return tree
+            }
+
+
+            tree.add_node(start_node.label.clone());
+
+            while tree.nodes.len() < self.nodes.len() {
+                let (min_edge, weight) = edges.pop().unwrap();
+                println!("Dequeue edge {}", min_edge.to_string());
+                let next_node = &min_edge.to;
+                if tree.contains_node(&next_node.label) {
+                    continue
                 }
-                println!("{:?}", current);
-                visited.insert(current.clone());
-                for neighbour in self.get_adjacent_nodes(&current).unwrap() {
-                    if !visited.contains(&neighbour) {
-                        stack.push(neighbour);
+
+                tree.add_node(next_node.label.clone());
+                tree.add_edge(min_edge.from.label.clone(), next_node.label.clone(), min_edge.weight)
+                    .expect("both endpoints were just added to the tree");
+                next_node.get_edges().iter().for_each(|edge| {
+                    if !tree.contains_node(&edge.to.label) {
+                        edges.push(edge.clone(), Reverse(edge.weight));
                     }
-                }
+                });
             }
+
+            tree
+
         }
 
-        pub fn breadth_first_traversal(&mut self, root: String) {
-            let node = unwrap_or_return!(self.node.get(&root).cloned());
-            let mut visited: HashSet<GraphNode> = HashSet::new();
-            let mut queue: Vec<GraphNode> = Vec::new();
-            queue.push(node.clone());
-            while !queue.is_empty() {
-                let current = queue.remove(0);
-                if visited.contains(&current) {
+        pub fn contains_node(&self, label: &String) -> bool {
+            self.nodes.contains_key(label)
+        }
+
+        pub fn nodes(&self) -> Vec<String> {
+            let mut nodes = self.nodes.keys().cloned().collect::<Vec<String>>();
+            nodes.sort();
+            nodes
+        }
+
+        fn neighbours(&self, label: &String) -> Vec<String> {
+            let mut neighbours = self.nodes[label]
+                .edges
+                .iter()
+                .map(|edge| edge.to.label.clone())
+                .collect::<Vec<String>>();
+            neighbours.sort();
+            neighbours.dedup();
+            neighbours
+        }
+
+        pub fn is_bipartite(&self) -> Bipartition {
+            let mut colour: HashMap<String, bool> = HashMap::new();
+            let mut parent: HashMap<String, String> = HashMap::new();
+            for start in self.nodes() {
+                if colour.contains_key(&start) {
                     continue;
                 }
-                println!("{:?}", current);
-                visited.insert(current.clone());
-                for neighbour in self.get_adjacent_nodes(&current).unwrap() {
-                    if !visited.contains(&neighbour) {
-                        queue.push(neighbour);
+                colour.insert(start.clone(), false);
+                let mut queue = VecDeque::from([start]);
+                while let Some(current) = queue.pop_front() {
+                    let current_colour = colour[&current];
+                    for neighbour in self.neighbours(&current) {
+                        match colour.get(&neighbour) {
+                            None => {
+                                colour.insert(neighbour.clone(), !current_colour);
+                                parent.insert(neighbour.clone(), current.clone());
+                                queue.push_back(neighbour);
+                            }
+                            Some(&neighbour_colour) if neighbour_colour == current_colour => {
+                                return Bipartition::OddCycle(self.odd_cycle(current, neighbour, &parent));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            let (mut left, mut right): (Vec<String>, Vec<String>) = (Vec::new(), Vec::new());
+            for (label, is_right) in colour {
+                if is_right {
+                    right.push(label);
+                } else {
+                    left.push(label);
+                }
+            }
+            left.sort();
+            right.sort();
+            Bipartition::Colouring(left, right)
+        }
+
+        // `first` and `second` share a colour and an edge, so joining their paths to the
+        // breadth first root at the lowest common ancestor closes a cycle of odd length
+        fn odd_cycle(&self, first: String, second: String, parent: &HashMap<String, String>) -> Vec<String> {
+            let path_to_root = |mut label: String| {
+                let mut path = vec![label.clone()];
+                while let Some(next) = parent.get(&label) {
+                    path.push(next.clone());
+                    label = next.clone();
+                }
+                path
+            };
+            let first_path = path_to_root(first);
+            let second_path = path_to_root(second);
+            let second_index = second_path
+                .iter()
+                .position(|label| first_path.contains(label))
+                .unwrap();
+            let first_index = first_path
+                .iter()
+                .position(|label| *label == second_path[second_index])
+                .unwrap();
+
+            let mut cycle = first_path[..=first_index].to_vec();
+            cycle.extend(second_path[..second_index].iter().rev().cloned());
+            cycle
+        }
+
+        // `left` is the caller's side of the partition (the workers), every other node is on
+        // the right. None when a label is unknown or an edge stays within one side.
+        fn sides(&self, left: &[&str]) -> Option<(Vec<String>, Vec<String>)> {
+            let left_side: HashSet<&str> = left.iter().copied().collect();
+            if left_side.iter().any(|label| !self.nodes.contains_key(*label)) {
+                return None;
+            }
+            for label in self.nodes.keys() {
+                let on_left = left_side.contains(label.as_str());
+                if self.neighbours(label).iter().any(|neighbour| left_side.contains(neighbour.as_str()) == on_left) {
+                    return None;
+                }
+            }
+            Some(self.nodes().into_iter().partition(|label| left_side.contains(label.as_str())))
+        }
+
+        // Pairs come back as (left, right) for the sides given by `sides`
+        pub fn maximum_matching(&self, left: &[&str]) -> Option<Vec<(String, String)>> {
+            let (left, right) = self.sides(left)?;
+            let right_index: HashMap<&String, usize> =
+                right.iter().enumerate().map(|(index, label)| (label, index)).collect();
+            let adjacency: Vec<Vec<usize>> = left
+                .iter()
+                .map(|label| {
+                    self.neighbours(label)
+                        .iter()
+                        .map(|neighbour| right_index[neighbour])
+                        .collect()
+                })
+                .collect();
+
+            let mut pair_left: Vec<Option<usize>> = vec![None; left.len()];
+            let mut pair_right: Vec<Option<usize>> = vec![None; right.len()];
+            let mut distance: Vec<usize> = vec![usize::MAX; left.len()];
+            while matching_layers(&adjacency, &pair_left, &pair_right, &mut distance) {
+                for node in 0..left.len() {
+                    if pair_left[node].is_none() {
+                        augment(node, &adjacency, &mut distance, &mut pair_left, &mut pair_right);
                     }
                 }
             }
+
+            let pairs = pair_left
+                .iter()
+                .enumerate()
+                .filter_map(|(node, pair)| pair.map(|other| (left[node].clone(), right[other].clone())))
+                .collect();
+            Some(pairs)
+        }
+
+        // Cheapest assignment between the sides given by `sides`, as (left, right) pairs.
+        // Pairs without an edge are only used when nothing else fits, and are left out of
+        // the result.
+        // The `missing` penalty outweighs any cost difference, so this first maximizes the
+        // number of matched pairs and only then minimizes their cost.
+        pub fn min_cost_assignment(&self, left: &[&str]) -> Option<(Vec<(String, String)>, i64)> {
+            let (left, right) = self.sides(left)?;
+            let mut weights: HashMap<(&str, &str), i64> = HashMap::new();
+            for node in &left {
+                for edge in &self.nodes[node].edges {
+                    let weight = weights
+                        .entry((edge.from.label.as_str(), edge.to.label.as_str()))
+                        .or_insert(edge.weight as i64);
+                    *weight = min(*weight, edge.weight as i64);
+                }
+            }
+
+            // Larger than any difference between two sets of real edges
+            let missing = 2 * weights.values().map(|weight| weight.abs()).sum::<i64>() + 1;
+            let build = |row: &String, column: &String| {
+                *weights.get(&(row.as_str(), column.as_str())).unwrap_or(&missing)
+            };
+            let costs: Vec<Vec<i64>> = if left.len() <= right.len() {
+                left.iter().map(|row| right.iter().map(|column| build(row, column)).collect()).collect()
+            } else {
+                right.iter().map(|row| left.iter().map(|column| build(column, row)).collect()).collect()
+            };
+
+            let mut pairs = Vec::new();
+            let mut cost = 0;
+            for (row, column) in assignment::solve(&costs).pairs {
+                let (from, to) = if left.len() <= right.len() {
+                    (&left[row], &right[column])
+                } else {
+                    (&left[column], &right[row])
+                };
+                if let Some(&weight) = weights.get(&(from.as_str(), to.as_str())) {
+                    pairs.push((from.clone(), to.clone()));
+                    cost += weight;
+                }
+            }
+            pairs.sort();
+            Some((pairs, cost))
+        }
+
+        // Every undirected edge once, with the smaller label first
+        pub fn edges(&self) -> Vec<(String, String, i32)> {
+            // Other than self loops every edge is stored on both endpoints, keep the copy
+            // held by the smaller label
+            let mut edges: Vec<(String, String, i32)> = self
+                .nodes
+                .values()
+                .flat_map(|node| node.edges.iter())
+                .filter(|edge| edge.from.label <= edge.to.label)
+                .map(|edge| (edge.from.label.clone(), edge.to.label.clone(), edge.weight))
+                .collect();
+            edges.sort();
+            edges
+        }
+
+        fn to_document(&self) -> GraphDocument {
+            let mut document = GraphDocument::new(false);
+            for node in self.nodes() {
+                document.add_node(node);
+            }
+            for (from, to, weight) in self.edges() {
+                document.add_edge(from, to, Some(weight));
+            }
+            document
         }
 
-        fn get_adjacent_nodes(&self, node: &GraphNode) -> Option<Vec<GraphNode>> {
-            self.adjacency_list.get(&node.label).cloned()
+        fn from_document(document: GraphDocument) -> Self {
+            let mut graph = WeightedGraphs::new();
+            for node in document.nodes {
+                graph.add_node(node);
+            }
+            for edge in document.edges {
+                graph
+                    .add_edge(edge.from, edge.to, edge.weight.unwrap_or(0))
+                    .expect("document nodes are added before its edges");
+            }
+            graph
         }
 
-        pub fn topological_sort(&mut self) -> Vec<String> {
-            let mut visited: HashSet<GraphNode> = HashSet::new();
-            let mut stack: Stack<GraphNode> = Stack::new();
-            let current = self.node.clone();
-            for node in current.values() {
-                self.topological_sort_(node.clone(), &mut visited, &mut stack);
+        pub fn to_dot(&self) -> String {
+            graph_io::to_dot(&self.to_document())
+        }
+
+        pub fn from_dot(input: &str) -> Result<Self, ParseError> {
+            graph_io::parse_dot(input, false, true).map(Self::from_document)
+        }
+
+        pub fn to_edge_list(&self) -> String {
+            graph_io::to_edge_list(&self.to_document())
+        }
+
+        pub fn from_edge_list(input: &str) -> Result<Self, ParseError> {
+            graph_io::parse_edge_list(input, false, true).map(Self::from_document)
+        }
+
+        pub fn to_adjacency_json(&self) -> String {
+            graph_io::to_adjacency_json(&self.to_document(), true)
+        }
+
+        pub fn from_adjacency_json(input: &str) -> Result<Self, ParseError> {
+            graph_io::parse_adjacency_json(input, false, true).map(Self::from_document)
+        }
+    }
+
+    // Breadth first layering from the free left nodes, true while an augmenting path exists
+    fn matching_layers(
+        adjacency: &[Vec<usize>],
+        pair_left: &[Option<usize>],
+        pair_right: &[Option<usize>],
+        distance: &mut [usize],
+    ) -> bool {
+        let mut queue = VecDeque::new();
+        for node in 0..adjacency.len() {
+            if pair_left[node].is_none() {
+                distance[node] = 0;
+                queue.push_back(node);
+            } else {
+                distance[node] = usize::MAX;
             }
+        }
 
-            let mut sorted: Vec<String> = Vec::new();
-            // println!("{:?}", stack);
+        let mut found = false;
+        while let Some(node) = queue.pop_front() {
+            for &other in &adjacency[node] {
+                match pair_right[other] {
+                    None => found = true,
+                    Some(next) if distance[next] == usize::MAX => {
+                        distance[next] = distance[node] + 1;
+                        queue.push_back(next);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        found
+    }
 
-            while !stack.is_empty() {
-                sorted.push(stack.pop().unwrap().label)
+    fn augment(
+        node: usize,
+        adjacency: &[Vec<usize>],
+        distance: &mut [usize],
+        pair_left: &mut [Option<usize>],
+        pair_right: &mut [Option<usize>],
+    ) -> bool {
+        for &other in &adjacency[node] {
+            let can_extend = match pair_right[other] {
+                None => true,
+                Some(next) => {
+                    distance[next] == distance[node] + 1
+                        && augment(next, adjacency, distance, pair_left, pair_right)
+                }
+            };
+            if can_extend {
+                pair_left[node] = Some(other);
+                pair_right[other] = Some(node);
+                return true;
             }
+        }
+        distance[node] = usize::MAX;
+        false
+    }
 
-            sorted
+    pub fn run () {
+        // let mut graph = WeightedGraphs::new();
+        // graph.add_node("A".to_string());
+        // graph.add_node("B".to_string());
+        // graph.add_node("C".to_string());
+        // graph.add_edge("A".to_string(), "B".to_string(), 3);
+        // graph.add_edge("A".to_string(), "C".to_string(), 2);
+        // println!("{}", graph.to_string());
+
+        let mut graph = WeightedGraphs::new();
+        graph.add_node("A".to_string());
+        graph.add_node("B".to_string());
+        graph.add_node("C".to_string());
+        graph.add_edge("A".to_string(), "B".to_string(), 1).unwrap();
+        graph.add_edge("B".to_string(), "C".to_string(), 2).unwrap();
+        graph.add_edge("A".to_string(), "C".to_string(), 10).unwrap();
+        println!("{}", graph.to_string());
+        graph.get_edges("B".to_string());
+        println!();
+        println!("shortest path: {}", graph.get_shortest_path("A".to_string(), "C".to_string()).to_string());
+        println!("{:?}", graph.shortest_distances("A", heaps::DaryHeap::new(4)));
+        println!("{:?}", graph.shortest_distances("A", heaps::Heap::new(1)));
+        println!("{:?}", graph.shortest_distances("A", heaps::PairingHeap::new()));
+
+        // let mut graph = WeightedGraphs::new();
+        // graph.add_node("A".to_string());
+        // graph.add_node("B".to_string());
+        // graph.add_node("C".to_string());
+        // graph.add_edge("A".to_string(), "B".to_string(), 0);
+        // graph.add_edge("B".to_string(), "C".to_string(), 0);
+        // graph.add_edge("C".to_string(), "A".to_string(), 0);
+        // // graph.add_edge("C".to_string(), "A".to_string(), 10);
+        // println!("{}", graph.to_string());
+        // println!("{}", graph.has_cycle());
+
+        let mut graph = WeightedGraphs::new();
+        graph.add_node("A".to_string());
+        graph.add_node("B".to_string());
+        graph.add_node("C".to_string());
+        graph.add_node("D".to_string());
+        graph.add_edge("A".to_string(), "B".to_string(), 3).unwrap();
+        graph.add_edge("B".to_string(), "D".to_string(), 4).unwrap();
+        graph.add_edge("C".to_string(), "D".to_string(), 5).unwrap();
+        graph.add_edge("A".to_string(), "C".to_string(), 1).unwrap();
+        graph.add_edge("B".to_string(), "C".to_string(), 2).unwrap();
+        // println!("{}", graph.to_string());
+        let tree = graph.get_minimum_spanning_tree();
+        println!("{}", tree.to_string());
+
+        let policy = EdgePolicy::new().parallel_edges(ParallelEdges::KeepMinWeight);
+        let mut graph = WeightedGraphs::with_policy(policy);
+        graph.add_node("A".to_string());
+        graph.add_node("B".to_string());
+        graph.add_edge("A".to_string(), "B".to_string(), 7).unwrap();
+        graph.add_edge("A".to_string(), "B".to_string(), 4).unwrap();
+        println!("{}", graph.to_string());
+
+        println!("{}", tree.to_dot());
+        let graph = WeightedGraphs::from_edge_list("A B 3\nB C 2\nD\n").unwrap();
+        println!("{}", graph.to_adjacency_json());
+        println!("{:?}", WeightedGraphs::from_edge_list("A B\n").err());
+
+        let graph = WeightedGraphs::from_edge_list("w1 j1 1\nw1 j2 1\nw2 j1 1\nw3 j2 1\nw3 j3 1\n").unwrap();
+        println!("{:?}", graph.is_bipartite());
+        println!("{:?}", graph.maximum_matching(&["w1", "w2", "w3"]));
+        println!("{:?}", graph.maximum_matching(&["j1", "j2", "j3"]));
+        let graph = WeightedGraphs::from_edge_list("w1 j1 4\nw1 j2 1\nw2 j1 2\nw2 j2 3\nw3 j3 5\n").unwrap();
+        println!("{:?}", graph.min_cost_assignment(&["w1", "w2", "w3"]));
+        let graph = WeightedGraphs::from_edge_list("A B 1\nB C 1\nC A 1\n").unwrap();
+        println!("{:?}", graph.is_bipartite());
+    }
+}
+
+
+pub mod graph_io {
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeMap, HashMap};
+    use std::fmt;
+    use std::iter::Peekable;
+    use std::str::Chars;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ParseError {
+        pub line: usize,
+        pub column: usize,
+        pub message: String,
+    }
+
+    impl ParseError {
+        pub fn new(line: usize, column: usize, message: String) -> Self {
+            Self {
+                line,
+                column,
+                message,
+            }
+        }
+    }
+
+    impl fmt::Display for ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
         }
+    }
 
-        fn topological_sort_(&mut self, node: GraphNode, visited: &mut HashSet<GraphNode>, stack: &mut Stack<GraphNode>) {
-            if visited.contains(&node) {
-                return;
+    impl std::error::Error for ParseError {}
+
+    impl From<serde_json::Error> for ParseError {
+        fn from(error: serde_json::Error) -> Self {
+            // serde_json appends "at line X column Y" to its messages, we keep the position separately
+            let message = error.to_string();
+            let message = match message.rfind(" at line ") {
+                Some(index) => message[..index].to_string(),
+                None => message,
+            };
+            Self::new(error.line(), error.column(), message)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DocumentEdge {
+        pub from: String,
+        pub to: String,
+        pub weight: Option<i32>,
+    }
+
+    // Format independent view of a graph. Undirected edges are listed once.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct GraphDocument {
+        pub directed: bool,
+        pub nodes: Vec<String>,
+        pub edges: Vec<DocumentEdge>,
+    }
+
+    impl GraphDocument {
+        pub fn new(directed: bool) -> Self {
+            Self {
+                directed,
+                nodes: Vec::new(),
+                edges: Vec::new(),
             }
+        }
 
-            // [P, B, X, A]
-            visited.insert(node.clone());
-            for neighbour in self.get_adjacent_nodes(&node).clone().unwrap() {
-                // println!("neighbour {:?}", neighbour);
-                // P
-                self.topological_sort_(neighbour, visited, stack);
+        pub fn add_node(&mut self, label: String) {
+            if !self.nodes.contains(&label) {
+                self.nodes.push(label);
             }
+        }
 
-            // [P, B, A, X]
-            stack.push(node);
-            // println!("stack result {:?}", stack);
+        pub fn add_edge(&mut self, from: String, to: String, weight: Option<i32>) {
+            self.add_node(from.clone());
+            self.add_node(to.clone());
+            self.edges.push(DocumentEdge { from, to, weight });
         }
+    }
 
-        pub fn has_cycle(&mut self) -> bool {
-            let mut all: HashSet<GraphNode> = HashSet::new();
-            self.node.values().for_each(|node| {
-                all.insert(node.clone());
-            });
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Token {
+        Id(String),
+        Arrow,
+        Dashes,
+        LeftBrace,
+        RightBrace,
+        LeftBracket,
+        RightBracket,
+        Equals,
+        Semicolon,
+        Comma,
+        Newline,
+    }
 
-            let mut visiting: HashSet<GraphNode> = HashSet::new();
-            let mut visited: HashSet<GraphNode> = HashSet::new();
-            println!("{:?}", all);
+    struct Lexer<'a> {
+        chars: Peekable<Chars<'a>>,
+        line: usize,
+        column: usize,
+        newlines: bool,
+    }
 
-            while !all.is_empty() {
-                let current = all.iter().next().unwrap().clone();
-                if self.has_cycle_(current, &mut all, &mut visiting, &mut visited) {
-                    return true
-                }
+    impl<'a> Lexer<'a> {
+        fn new(input: &'a str, newlines: bool) -> Self {
+            Self {
+                chars: input.chars().peekable(),
+                line: 1,
+                column: 1,
+                newlines,
             }
-            false
         }
 
-        fn has_cycle_(&self, root: GraphNode, all: &mut HashSet<GraphNode>, visiting: &mut HashSet<GraphNode>, visited: &mut HashSet<GraphNode>) -> bool {
-            all.remove(&root);
-            visiting.insert(root.clone());
+        fn bump(&mut self) -> Option<char> {
+            let c = self.chars.next()?;
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+            Some(c)
+        }
 
-            for neighbour in self.get_adjacent_nodes(&root).unwrap() {
-                println!("neighbour {:?}", neighbour);
-                if visited.contains(&neighbour) {
-                    continue
-                }
+        fn error(&self, message: String) -> ParseError {
+            ParseError::new(self.line, self.column, message)
+        }
 
-                if visiting.contains(&neighbour) {
-                    return true
+        fn skip_comment(&mut self) {
+            while let Some(&c) = self.chars.peek() {
+                if c == '\n' {
+                    break;
                 }
+                self.bump();
+            }
+        }
 
-                if self.has_cycle_(neighbour, all, visiting, visited) {
-                    return true
+        // Returns the next token together with the line and column it starts at
+        fn next_token(&mut self) -> Result<Option<(Token, usize, usize)>, ParseError> {
+            loop {
+                let c = match self.chars.peek() {
+                    Some(&c) => c,
+                    None => return Ok(None),
+                };
+                let (line, column) = (self.line, self.column);
+                if c == '\n' {
+                    self.bump();
+                    if self.newlines {
+                        return Ok(Some((Token::Newline, line, column)));
+                    }
+                    continue;
+                }
+                if c.is_whitespace() {
+                    self.bump();
+                    continue;
+                }
+                if c == '#' {
+                    self.skip_comment();
+                    continue;
                 }
+
+                self.bump();
+                let token = match c {
+                    '{' => Token::LeftBrace,
+                    '}' => Token::RightBrace,
+                    '[' => Token::LeftBracket,
+                    ']' => Token::RightBracket,
+                    '=' => Token::Equals,
+                    ';' => Token::Semicolon,
+                    ',' => Token::Comma,
+                    '"' => Token::Id(self.quoted(line, column)?),
+                    '/' if self.chars.peek() == Some(&'/') => {
+                        self.skip_comment();
+                        continue;
+                    }
+                    '-' => match self.chars.peek() {
+                        Some('>') => {
+                            self.bump();
+                            Token::Arrow
+                        }
+                        Some('-') => {
+                            self.bump();
+                            Token::Dashes
+                        }
+                        Some(next) if next.is_ascii_digit() => {
+                            let mut id = String::from("-");
+                            id.push_str(&self.bare());
+                            Token::Id(id)
+                        }
+                        _ => {
+                            return Err(ParseError::new(line, column, "unexpected character '-'".to_string()))
+                        }
+                    },
+                    c if is_bare_char(c) => {
+                        let mut id = c.to_string();
+                        id.push_str(&self.bare());
+                        Token::Id(id)
+                    }
+                    c => {
+                        return Err(ParseError::new(line, column, format!("unexpected character '{}'", c)))
+                    }
+                };
+                return Ok(Some((token, line, column)));
             }
+        }
 
-            visiting.remove(&root);
-            visited.insert(root.clone());
-            return false
+        fn bare(&mut self) -> String {
+            let mut id = String::new();
+            while let Some(&c) = self.chars.peek() {
+                if !is_bare_char(c) {
+                    break;
+                }
+                id.push(c);
+                self.bump();
+            }
+            id
         }
 
-        pub fn print(&mut self) {
-            for (key, value) in &self.adjacency_list {
-                let targets = self.adjacency_list.get(key).unwrap();
-                if !targets.is_empty() {
-                    let label = targets
-                        .iter()
-                        .map(|node| node.label.clone())
-                        .collect::<Vec<String>>();
-                    println!("{} is connected to {:?}", key, label);
+        fn quoted(&mut self, line: usize, column: usize) -> Result<String, ParseError> {
+            let mut id = String::new();
+            loop {
+                match self.bump() {
+                    Some('"') => return Ok(id),
+                    Some('\\') => match self.bump() {
+                        Some(c) => id.push(c),
+                        None => break,
+                    },
+                    Some(c) => id.push(c),
+                    None => break,
                 }
             }
+            Err(ParseError::new(line, column, "unterminated string".to_string()))
         }
     }
 
-    pub fn run() {
-        // let mut graph = Graph::new();
-        // graph.add_node("kevin".to_string());
-        // graph.add_node("vina".to_string());
-        // graph.add_node("becca".to_string());
-        // graph.add_edge("kevin".to_string(), "vina".to_string());
-        // graph.add_edge("kevin".to_string(), "becca".to_string());
-
-        // graph.remove_edge("kevin".to_string(), "d".to_string());
-        // graph.remove_node("kevin".to_string());
-        // graph.add_edge("vina".to_string(), "becca".to_string());
-        // graph.print();
-        // println!("{:?}", graph);
+    impl fmt::Display for Token {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Token::Id(id) => write!(f, "'{}'", id),
+                Token::Arrow => write!(f, "'->'"),
+                Token::Dashes => write!(f, "'--'"),
+                Token::LeftBrace => write!(f, "'{{'"),
+                Token::RightBrace => write!(f, "'}}'"),
+                Token::LeftBracket => write!(f, "'['"),
+                Token::RightBracket => write!(f, "']'"),
+                Token::Equals => write!(f, "'='"),
+                Token::Semicolon => write!(f, "';'"),
+                Token::Comma => write!(f, "','"),
+                Token::Newline => write!(f, "end of line"),
+            }
+        }
+    }
 
-        // let mut graph = Graph::new();
-        // graph.add_node("A".to_string());
-        // graph.add_node("B".to_string());
-        // graph.add_node("C".to_string());
-        // graph.add_node("D".to_string());
-        // graph.add_edge("A".to_string(), "B".to_string());
-        // graph.add_edge("B".to_string(), "D".to_string());
-        // graph.add_edge("D".to_string(), "C".to_string());
-        // graph.add_edge("A".to_string(), "C".to_string());
-        // graph.print();
-        // graph.breadth_first_traversal("D".to_string());
+    fn is_bare_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || c == '.'
+    }
 
-        // let mut graph = Graph::new();
-        // graph.add_node("X".to_string());
-        // graph.add_node("P".to_string());
-        // graph.add_node("A".to_string());
-        // graph.add_node("B".to_string());
-        // graph.add_edge("X".to_string(), "A".to_string());
-        // graph.add_edge("X".to_string(), "B".to_string());
-        // graph.add_edge("A".to_string(), "P".to_string());
-        // graph.add_edge("B".to_string(), "P".to_string());
-        // println!("{:?}", graph.topological_sort());
-        // graph.print();
+    fn is_bare(label: &str) -> bool {
+        !label.is_empty() && label.chars().all(is_bare_char)
+    }
 
-        let mut graph = Graph::new();
-        graph.add_node("A".to_string());
-        graph.add_node("B".to_string());
-        graph.add_node("C".to_string());
-        graph.add_edge("A".to_string(), "B".to_string());
-        graph.add_edge("B".to_string(), "C".to_string());
-        graph.add_edge("C".to_string(), "A".to_string());
-        println!("{:?}", graph.has_cycle());
+    fn quote(label: &str) -> String {
+        format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
     }
-}
 
-pub mod ungraphs {
-    use std::cmp::{min, Reverse};
-    use std::collections::{HashMap, HashSet};
-    use crate::part2::graphs;
-    use crate::part2::graphs::Stack;
+    fn parse_weight(value: &str, line: usize, column: usize) -> Result<i32, ParseError> {
+        value
+            .parse::<i32>()
+            .map_err(|_| ParseError::new(line, column, format!("invalid weight '{}'", value)))
+    }
 
-    macro_rules! unwrap_or_return {
-        ($option:expr) => {
-            match $option {
-                Some(value) => value,
-                None => return,
-            }
+    pub fn to_dot(document: &GraphDocument) -> String {
+        let (keyword, operator) = if document.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
         };
+        let mut result = format!("{} {{\n", keyword);
+        for node in &document.nodes {
+            result.push_str(&format!("    {};\n", quote(node)));
+        }
+        for edge in &document.edges {
+            result.push_str(&format!("    {} {} {}", quote(&edge.from), operator, quote(&edge.to)));
+            if let Some(weight) = edge.weight {
+                result.push_str(&format!(" [weight={}, label=\"{}\"]", weight, weight));
+            }
+            result.push_str(";\n");
+        }
+        result.push_str("}\n");
+        result
     }
 
-    #[derive(Debug)]
-    struct Path {
-        nodes: Vec<String>,
+    struct DotParser<'a> {
+        lexer: Lexer<'a>,
+        peeked: Option<(Token, usize, usize)>,
     }
 
-    impl Path {
-        pub fn new() -> Self {
-            Self {
-                nodes: Vec::new(),
+    impl<'a> DotParser<'a> {
+        fn peek(&mut self) -> Result<Option<&(Token, usize, usize)>, ParseError> {
+            if self.peeked.is_none() {
+                self.peeked = self.lexer.next_token()?;
             }
+            Ok(self.peeked.as_ref())
         }
 
-        pub fn add_node(&mut self, node: String) {
-            self.nodes.push(node);
+        fn next(&mut self) -> Result<(Token, usize, usize), ParseError> {
+            self.peek()?;
+            match self.peeked.take() {
+                Some(token) => Ok(token),
+                None => Err(self.lexer.error("unexpected end of input".to_string())),
+            }
         }
 
-        pub fn to_string(&self) -> String {
-            self.nodes.join(" -> ")
+        fn next_id(&mut self) -> Result<(String, usize, usize), ParseError> {
+            match self.next()? {
+                (Token::Id(id), line, column) => Ok((id, line, column)),
+                (token, line, column) => Err(ParseError::new(
+                    line,
+                    column,
+                    format!("expected identifier, found {}", token),
+                )),
+            }
         }
-    }
 
-    #[derive(Debug)]
-    struct PriorityQueue<T: Ord> {
-        elements: Vec<T>,
-    }
+        fn next_is(&mut self, expected: &Token) -> Result<bool, ParseError> {
+            Ok(matches!(self.peek()?, Some((token, _, _)) if token == expected))
+        }
 
-    impl<T: Ord> PriorityQueue<T> {
-        pub fn new() -> Self {
-            Self {
-                elements: Vec::new(),
+        // Returns the weight attribute (if any) with the position of its value
+        fn attributes(&mut self) -> Result<Option<(String, usize, usize)>, ParseError> {
+            let mut weight = None;
+            while self.next_is(&Token::LeftBracket)? {
+                self.next()?;
+                loop {
+                    if self.next_is(&Token::RightBracket)? {
+                        self.next()?;
+                        break;
+                    }
+                    let (key, _, _) = self.next_id()?;
+                    match self.next()? {
+                        (Token::Equals, _, _) => {}
+                        (token, line, column) => {
+                            return Err(ParseError::new(line, column, format!("expected '=', found {}", token)))
+                        }
+                    }
+                    let value = self.next_id()?;
+                    if key == "weight" {
+                        weight = Some(value);
+                    }
+                    if self.next_is(&Token::Comma)? || self.next_is(&Token::Semicolon)? {
+                        self.next()?;
+                    }
+                }
             }
+            Ok(weight)
         }
+    }
 
-        pub fn enqueue(&mut self, item: T) {
-            self.elements.push(item);
-            // Sort in ascending order
-            self.elements.sort();
-        }
+    pub fn parse_dot(input: &str, directed: bool, weighted: bool) -> Result<GraphDocument, ParseError> {
+        let mut parser = DotParser {
+            lexer: Lexer::new(input, false),
+            peeked: None,
+        };
+        let mut document = GraphDocument::new(directed);
 
-        pub fn dequeue(&mut self) -> Option<T> {
-            self.elements.pop()
+        let (mut keyword, mut line, mut column) = parser.next_id()?;
+        if keyword == "strict" {
+            (keyword, line, column) = parser.next_id()?;
+        }
+        match (keyword.as_str(), directed) {
+            ("digraph", true) | ("graph", false) => {}
+            ("digraph", false) => return Err(ParseError::new(line, column, "expected an undirected graph".to_string())),
+            ("graph", true) => return Err(ParseError::new(line, column, "expected a directed graph".to_string())),
+            _ => return Err(ParseError::new(line, column, format!("expected 'graph' or 'digraph', found '{}'", keyword))),
+        }
+        if !parser.next_is(&Token::LeftBrace)? {
+            parser.next_id()?;
+        }
+        match parser.next()? {
+            (Token::LeftBrace, _, _) => {}
+            (token, line, column) => return Err(ParseError::new(line, column, format!("expected '{{', found {}", token))),
         }
 
-        pub fn is_empty(&self) -> bool {
-            self.elements.is_empty()
+        loop {
+            let (id, line, column) = match parser.next()? {
+                (Token::RightBrace, _, _) => break,
+                (Token::Semicolon, _, _) => continue,
+                (Token::Id(id), line, column) => (id, line, column),
+                (token, line, column) => {
+                    return Err(ParseError::new(line, column, format!("unexpected {}", token)))
+                }
+            };
+
+            // Default attribute statements and graph attributes carry no nodes or edges
+            if matches!(id.as_str(), "graph" | "node" | "edge") && parser.next_is(&Token::LeftBracket)? {
+                parser.attributes()?;
+                continue;
+            }
+            if parser.next_is(&Token::Equals)? {
+                parser.next()?;
+                parser.next_id()?;
+                continue;
+            }
+
+            let mut chain = vec![id];
+            while let Some((Token::Arrow | Token::Dashes, _, _)) = parser.peek()? {
+                let (operator, line, column) = parser.next()?;
+                if (operator == Token::Arrow) != directed {
+                    let expected = if directed { "->" } else { "--" };
+                    return Err(ParseError::new(line, column, format!("expected '{}'", expected)));
+                }
+                chain.push(parser.next_id()?.0);
+            }
+
+            let weight = match parser.attributes()? {
+                Some((value, line, column)) => Some(parse_weight(&value, line, column)?),
+                None => None,
+            };
+            if chain.len() == 1 {
+                document.add_node(chain.remove(0));
+                continue;
+            }
+            if weighted && weight.is_none() {
+                return Err(ParseError::new(line, column, "edge has no weight attribute".to_string()));
+            }
+            for pair in chain.windows(2) {
+                document.add_edge(pair[0].clone(), pair[1].clone(), if weighted { weight } else { None });
+            }
         }
 
-        pub fn peek(&self) -> Option<&T> {
-            self.elements.last()
+        if let Some((token, line, column)) = parser.peek()? {
+            return Err(ParseError::new(*line, *column, format!("unexpected {} after graph", token)));
         }
+        Ok(document)
+    }
 
-        pub fn count(&self) -> usize {
-            self.elements.len()
+    pub fn to_edge_list(document: &GraphDocument) -> String {
+        let format_label = |label: &str| {
+            if is_bare(label) {
+                label.to_string()
+            } else {
+                quote(label)
+            }
+        };
+        let mut result = String::new();
+        for node in &document.nodes {
+            let isolated = !document.edges.iter().any(|edge| &edge.from == node || &edge.to == node);
+            if isolated {
+                result.push_str(&format!("{}\n", format_label(node)));
+            }
+        }
+        for edge in &document.edges {
+            result.push_str(&format!("{} {}", format_label(&edge.from), format_label(&edge.to)));
+            if let Some(weight) = edge.weight {
+                result.push_str(&format!(" {}", weight));
+            }
+            result.push('\n');
         }
+        result
+    }
 
-        pub fn get_all(&self) -> &Vec<T> {
-            &self.elements
+    // One entry per line: `node`, `from to` or `from to weight`
+    pub fn parse_edge_list(input: &str, directed: bool, weighted: bool) -> Result<GraphDocument, ParseError> {
+        let mut lexer = Lexer::new(input, true);
+        let mut document = GraphDocument::new(directed);
+        let mut ids: Vec<(String, usize, usize)> = Vec::new();
+        loop {
+            let token = lexer.next_token()?;
+            match token {
+                Some((Token::Id(id), line, column)) => {
+                    if ids.len() == 3 {
+                        return Err(ParseError::new(line, column, "too many fields on line".to_string()));
+                    }
+                    ids.push((id, line, column));
+                    continue;
+                }
+                Some((Token::Newline, _, _)) | None => {}
+                Some((token, line, column)) => {
+                    return Err(ParseError::new(line, column, format!("unexpected {}", token)))
+                }
+            }
+
+            let mut fields = ids.drain(..);
+            match (fields.next(), fields.next(), fields.next()) {
+                (None, _, _) => {}
+                (Some((node, _, _)), None, _) => document.add_node(node),
+                (Some((from, _, _)), Some((to, line, column)), None) => {
+                    if weighted {
+                        let column = column + to.chars().count();
+                        return Err(ParseError::new(line, column, "missing weight".to_string()));
+                    }
+                    document.add_edge(from, to, None);
+                }
+                (Some((from, _, _)), Some((to, _, _)), Some((weight, line, column))) => {
+                    if !weighted {
+                        return Err(ParseError::new(line, column, "unexpected weight".to_string()));
+                    }
+                    let weight = parse_weight(&weight, line, column)?;
+                    document.add_edge(from, to, Some(weight));
+                }
+            }
+            drop(fields);
+            if token.is_none() {
+                return Ok(document);
+            }
         }
     }
 
-    #[derive(Debug, Eq, Hash, PartialEq, Clone, Ord, PartialOrd)]
-    struct GraphNode {
-        label: String,
-        edges: Vec<Edge>
+    type Neighbour = (String, Option<i32>);
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct WeightedNeighbour {
+        to: String,
+        weight: i32,
     }
-    impl GraphNode {
-        pub fn new(label: String) -> Self {
-            Self { label, edges: Vec::new() }
-        }
 
-        pub fn add_edge(&mut self, to: GraphNode, weight: i32) {
-            self.edges.push(Edge::new(self.clone(), to, weight));
+    // Each node maps to its neighbours; undirected edges appear under both endpoints,
+    // except self loops
+    pub fn to_adjacency_json(document: &GraphDocument, weighted: bool) -> String {
+        let mut adjacency: BTreeMap<String, Vec<(String, i32)>> = BTreeMap::new();
+        for node in &document.nodes {
+            adjacency.insert(node.clone(), Vec::new());
+        }
+        for edge in &document.edges {
+            let weight = edge.weight.unwrap_or(0);
+            if let Some(list) = adjacency.get_mut(&edge.from) {
+                list.push((edge.to.clone(), weight));
+            }
+            if !document.directed && edge.from != edge.to {
+                if let Some(list) = adjacency.get_mut(&edge.to) {
+                    list.push((edge.from.clone(), weight));
+                }
+            }
         }
 
-        pub fn get_edges(&self) -> Vec<Edge> {
-            self.edges.clone()
+        if weighted {
+            let adjacency: BTreeMap<String, Vec<WeightedNeighbour>> = adjacency
+                .into_iter()
+                .map(|(node, list)| {
+                    let list = list
+                        .into_iter()
+                        .map(|(to, weight)| WeightedNeighbour { to, weight })
+                        .collect();
+                    (node, list)
+                })
+                .collect();
+            serde_json::to_string_pretty(&adjacency).unwrap()
+        } else {
+            let adjacency: BTreeMap<String, Vec<String>> = adjacency
+                .into_iter()
+                .map(|(node, list)| (node, list.into_iter().map(|(to, _)| to).collect()))
+                .collect();
+            serde_json::to_string_pretty(&adjacency).unwrap()
         }
     }
 
-    #[derive(Debug, Eq, Hash, PartialEq, Clone, Ord, PartialOrd)]
-    struct Edge {
-        from: GraphNode,
-        to: GraphNode,
-        weight: i32
-    }
+    pub fn parse_adjacency_json(input: &str, directed: bool, weighted: bool) -> Result<GraphDocument, ParseError> {
+        let adjacency: Vec<(String, Vec<Neighbour>)> = if weighted {
+            let adjacency: BTreeMap<String, Vec<WeightedNeighbour>> = serde_json::from_str(input)?;
+            adjacency
+                .into_iter()
+                .map(|(node, list)| {
+                    (node, list.into_iter().map(|n| (n.to, Some(n.weight))).collect())
+                })
+                .collect()
+        } else {
+            let adjacency: BTreeMap<String, Vec<String>> = serde_json::from_str(input)?;
+            adjacency
+                .into_iter()
+                .map(|(node, list)| (node, list.into_iter().map(|to| (to, None)).collect()))
+                .collect()
+        };
 
-    impl Edge {
-        pub fn new(from: GraphNode, to: GraphNode, weight: i32) -> Self {
-            Self {
-                from,
-                to,
-                weight
+        let mut document = GraphDocument::new(directed);
+        for (node, _) in &adjacency {
+            document.add_node(node.clone());
+        }
+        if directed {
+            for (from, list) in adjacency {
+                for (to, weight) in list {
+                    document.add_edge(from.clone(), to, weight);
+                }
+            }
+            return Ok(document);
+        }
+
+        // An undirected edge is normally listed under both endpoints, so each pair of
+        // listings becomes one edge. Edges listed on one side only are kept as well, and
+        // a self loop is listed once.
+        let mut order: Vec<(String, String, Option<i32>)> = Vec::new();
+        let mut counts: HashMap<(String, String, Option<i32>), usize> = HashMap::new();
+        for (from, list) in adjacency {
+            for (to, weight) in list {
+                let key = if from <= to {
+                    (from.clone(), to, weight)
+                } else {
+                    (to, from.clone(), weight)
+                };
+                let count = counts.entry(key.clone()).or_insert(0);
+                if *count == 0 {
+                    order.push(key);
+                }
+                *count += 1;
+            }
+        }
+        for key in order {
+            let count = if key.0 == key.1 { counts[&key] } else { counts[&key].div_ceil(2) };
+            for _ in 0..count {
+                document.add_edge(key.0.clone(), key.1.clone(), key.2);
             }
         }
+        Ok(document)
+    }
 
-        pub fn to_string(&self) -> String {
-            format!("{} -> {} ({})", self.from.label, self.to.label, self.weight)
+}
+
+pub mod flows {
+    use crate::part2::graphs::{Graph, GraphError};
+    use std::cmp::min;
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    #[derive(Debug, Clone)]
+    struct FlowEdge {
+        to: usize,
+        capacity: i32,
+        flow: i32,
+    }
+
+    impl FlowEdge {
+        fn residual(&self) -> i32 {
+            self.capacity - self.flow
         }
     }
 
-    #[derive(Debug)]
-    struct WeightedGraphs {
-        nodes: HashMap<String, GraphNode>
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct EdgeFlow {
+        pub from: String,
+        pub to: String,
+        pub capacity: i32,
+        pub flow: i32,
     }
 
-    #[derive(Debug, Hash, Ord, Eq, PartialEq, PartialOrd)]
-    struct NodeEntry {
-        node: GraphNode,
-        priority: i32
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct MaxFlow {
+        // Summed over many i32 capacities, so it gets the wider type
+        pub value: i64,
+        pub flows: Vec<EdgeFlow>,
+        // Nodes still reachable from the source in the residual graph
+        pub source_side: Vec<String>,
+        pub cut: Vec<(String, String)>,
     }
 
-    impl NodeEntry {
-        pub fn new(node: GraphNode, priority: i32) -> Self {
-            Self {
-                node,
-                priority
-            }
-        }
+    // Directed capacity graph. Every edge is stored next to its residual edge,
+    // so edge `i` and `i ^ 1` are each other's reverse.
+    #[derive(Debug, Clone)]
+    pub struct FlowNetwork {
+        labels: Vec<String>,
+        indices: HashMap<String, usize>,
+        edges: Vec<FlowEdge>,
+        adjacency: Vec<Vec<usize>>,
     }
 
-    impl WeightedGraphs {
+    impl FlowNetwork {
         pub fn new() -> Self {
             Self {
-                nodes: HashMap::new()
+                labels: Vec::new(),
+                indices: HashMap::new(),
+                edges: Vec::new(),
+                adjacency: Vec::new(),
             }
         }
 
-        pub fn to_string(&self) -> String {
-            let mut result = String::new();
-            self.nodes.values().into_iter().for_each(
-                |node| {
-                    let edges = node.get_edges().iter().map(|edge| edge.to_string()).collect::<Vec<String>>().join(", ");
-                    result.push_str(&format!("{} -> [{}]\n", node.label, edges));
+        // The capacity is given per pair of nodes, so parallel edges of the graph become
+        // a single edge instead of adding the capacity up once per copy
+        pub fn from_graph<F>(graph: &Graph, capacity: F) -> Result<Self, GraphError>
+        where
+            F: Fn(&str, &str) -> i32,
+        {
+            let mut network = FlowNetwork::new();
+            for node in graph.nodes() {
+                network.add_node(node);
+            }
+            let mut added: HashSet<(String, String)> = HashSet::new();
+            for (from, to) in graph.edges() {
+                if !added.insert((from.clone(), to.clone())) {
+                    continue;
                 }
-            );
-            result
+                let capacity = capacity(&from, &to);
+                network.add_edge(from, to, capacity)?;
+            }
+            Ok(network)
         }
 
         pub fn add_node(&mut self, label: String) {
-            self.nodes.entry(label.to_owned()).or_insert(GraphNode::new(label));
-        }
-
-        pub fn add_edge(&mut self, from: String, to: String, weight: i32) {
-            let to_node = unwrap_or_return!(self.nodes.get(&to).cloned());
-            let from_node = unwrap_or_return!(self.nodes.get(&from).cloned());
-            if let Some(from) = self.nodes.get_mut(&from) {
-                from.add_edge(to_node, weight);
-            }
-            if let Some(to) = self.nodes.get_mut(&to) {
-                to.add_edge(from_node, weight);
+            if self.indices.contains_key(&label) {
+                return;
             }
+            self.indices.insert(label.clone(), self.labels.len());
+            self.labels.push(label);
+            self.adjacency.push(Vec::new());
         }
 
-
-
-        pub fn get_shortest_path(&self, from: String, to: String) -> Path {
-            let from_node = if let Some(from_node) = self.nodes.get(&from).cloned() {
-                from_node
-            } else {
-                return Path::new()
-            };
-            let to_node = if let Some(to_node) = self.nodes.get(&to).cloned() {
-                to_node
-            } else {
-                return Path::new()
-            };
-            println!("from {} to {}", from_node.label, to_node.label);
-
-            let mut distance: HashMap<GraphNode, i32> = HashMap::new();
-            for node in self.nodes.values() {
-                distance.insert(node.clone(), i32::MAX);
+        pub fn add_edge(&mut self, from: String, to: String, capacity: i32) -> Result<(), GraphError> {
+            let from_index = self.index_of(&from)?;
+            let to_index = self.index_of(&to)?;
+            if capacity < 0 {
+                return Err(GraphError::NegativeCapacity(from, to, capacity));
             }
-            distance.insert(from_node.clone(), 0);
-            distance.iter().for_each(|(key, value)| {
-                println!("distance {} -> {}", key.label, value);
+
+            self.adjacency[from_index].push(self.edges.len());
+            self.edges.push(FlowEdge {
+                to: to_index,
+                capacity,
+                flow: 0,
+            });
+            self.adjacency[to_index].push(self.edges.len());
+            self.edges.push(FlowEdge {
+                to: from_index,
+                capacity: 0,
+                flow: 0,
             });
+            Ok(())
+        }
 
-            let mut previous_nodes: HashMap<GraphNode, GraphNode> = HashMap::new();
-            let mut visited: HashSet<GraphNode> = HashSet::new();
-            let mut queue = priority_queue::PriorityQueue::new();
-            queue.push(NodeEntry::new(from_node.clone(), 0), Reverse(0));
-            println!("Added to queue {}", from_node.label);
-            println!();
+        fn index_of(&self, label: &String) -> Result<usize, GraphError> {
+            match self.indices.get(label) {
+                Some(&index) => Ok(index),
+                None => Err(GraphError::NodeNotFound(label.clone())),
+            }
+        }
 
-            while let Some((NodeEntry { node: current, .. }, _)) = queue.pop() {
-                visited.insert(current.clone());
-                println!("Visited [{}]", visited.iter().map(|x|x.label.clone()).collect::<Vec<String>>().join(", "));
+        // Dinic's algorithm, O(V^2 * E)
+        pub fn max_flow(&self, source: String, sink: String) -> Result<MaxFlow, GraphError> {
+            let source_index = self.index_of(&source)?;
+            let sink_index = self.index_of(&sink)?;
+            if source_index == sink_index {
+                return Err(GraphError::SameSourceAndSink(source));
+            }
 
-                println!("Processing Node {} == {}", current.label, current.get_edges().iter()
-                    .map(|x|x.to_string()).collect::<Vec<String>>().join(", "));
-                for edge in current.get_edges() {
-                    println!("processing Edge {} > {} with weight {}", edge.from.label,
-                             edge.to.label, edge.weight);
-                    let neighbor = self.nodes.get(&edge.to.label).cloned().unwrap();
-                    if visited.contains(&neighbor) {
-                        println!("Already visited {}", neighbor.label);
-                        continue;
+            let mut edges = self.edges.clone();
+            let mut value: i64 = 0;
+            while let Some(level) = self.build_levels(&edges, source_index, sink_index) {
+                let mut next_edge = vec![0; self.labels.len()];
+                loop {
+                    let pushed = self.push_flow(&mut edges, &level, &mut next_edge, source_index, sink_index, i32::MAX);
+                    if pushed == 0 {
+                        break;
                     }
+                    value += i64::from(pushed);
+                }
+            }
 
-                    let new_distance = distance[&current] + edge.weight;
-                    println!("New distance for {} is {}", neighbor.label, new_distance);
-                    if new_distance < distance[&neighbor] {
-                        println!("The new distance is less than the current distance {} < {}", new_distance, distance[&neighbor]);
-                        distance.insert(neighbor.clone(), new_distance);
-                        previous_nodes.insert(neighbor.clone(), current.clone());
-                        println!("Add to queue {} with distance {}", neighbor.label, new_distance);
-                        queue.push(NodeEntry::new(neighbor.clone(), new_distance), Reverse(new_distance));
+            let reachable = self.reachable(&edges, source_index);
+            let mut flows = Vec::new();
+            let mut cut = Vec::new();
+            for (from, list) in self.adjacency.iter().enumerate() {
+                for &index in list.iter().filter(|&&index| index % 2 == 0) {
+                    let edge = &edges[index];
+                    let (from_label, to_label) = (self.labels[from].clone(), self.labels[edge.to].clone());
+                    if reachable[from] && !reachable[edge.to] {
+                        cut.push((from_label.clone(), to_label.clone()));
                     }
-                    println!();
+                    flows.push(EdgeFlow {
+                        from: from_label,
+                        to: to_label,
+                        capacity: edge.capacity,
+                        flow: edge.flow,
+                    });
                 }
             }
+            let source_side = (0..self.labels.len())
+                .filter(|&node| reachable[node])
+                .map(|node| self.labels[node].clone())
+                .collect();
 
-            previous_nodes.iter().for_each(|(key, value)| {
-                println!("{} -> {}", key.label, value.label);
-            });
-
-            self.build_path(to_node, previous_nodes)
+            Ok(MaxFlow {
+                value,
+                flows,
+                source_side,
+                cut,
+            })
+        }
+
+        // Breadth first levels over the residual graph, None once the sink is unreachable
+        fn build_levels(&self, edges: &[FlowEdge], source: usize, sink: usize) -> Option<Vec<Option<usize>>> {
+            let mut level = vec![None; self.labels.len()];
+            let mut queue = VecDeque::new();
+            level[source] = Some(0);
+            queue.push_back(source);
+            while let Some(current) = queue.pop_front() {
+                for &index in &self.adjacency[current] {
+                    let edge = &edges[index];
+                    if edge.residual() > 0 && level[edge.to].is_none() {
+                        level[edge.to] = level[current].map(|depth| depth + 1);
+                        queue.push_back(edge.to);
+                    }
+                }
+            }
+            level[sink].map(|_| level)
         }
 
-        fn build_path(&self, to_node: GraphNode, previous_nodes: HashMap<GraphNode, GraphNode>) -> Path {
-            let mut path = Path::new();
-            let mut current_label = to_node.clone();
-
-            while let Some(prev_label) = previous_nodes.get(&current_label) {
-                path.add_node(current_label.label.clone());
-                current_label = prev_label.clone();
+        fn push_flow(
+            &self,
+            edges: &mut [FlowEdge],
+            level: &[Option<usize>],
+            next_edge: &mut [usize],
+            current: usize,
+            sink: usize,
+            limit: i32,
+        ) -> i32 {
+            if current == sink {
+                return limit;
             }
-            path.add_node(current_label.label);
-            path.nodes.reverse();
-            path
+            while next_edge[current] < self.adjacency[current].len() {
+                let index = self.adjacency[current][next_edge[current]];
+                let (to, residual) = (edges[index].to, edges[index].residual());
+                let is_next_level = level[to] == level[current].map(|depth| depth + 1);
+                if residual > 0 && is_next_level {
+                    let pushed = self.push_flow(edges, level, next_edge, to, sink, min(limit, residual));
+                    if pushed > 0 {
+                        edges[index].flow += pushed;
+                        edges[index ^ 1].flow -= pushed;
+                        return pushed;
+                    }
+                }
+                next_edge[current] += 1;
+            }
+            0
+        }
+
+        fn reachable(&self, edges: &[FlowEdge], source: usize) -> Vec<bool> {
+            let mut reachable = vec![false; self.labels.len()];
+            let mut stack = vec![source];
+            reachable[source] = true;
+            while let Some(current) = stack.pop() {
+                for &index in &self.adjacency[current] {
+                    let edge = &edges[index];
+                    if edge.residual() > 0 && !reachable[edge.to] {
+                        reachable[edge.to] = true;
+                        stack.push(edge.to);
+                    }
+                }
+            }
+            reachable
         }
+    }
 
-        pub fn get_edges(&self, node: String) {
-            let result = unwrap_or_return!(self.nodes.get(&node).cloned());
-            println!("Edges for node {}", result.label);
-            result.get_edges().iter().for_each(|edge| {
-                println!("{}", edge.to_string());
-            });
+    impl Default for FlowNetwork {
+        fn default() -> Self {
+            Self::new()
         }
+    }
 
-        pub fn has_cycle(&self) -> bool {
-            let mut visited: HashSet<String> = HashSet::new();
-            for node in self.nodes.values() {
-                println!("Checking node {}", node.label);
-                if !visited.contains(&node.label) &&
-                    self.has_cycle_(node.clone(), &mut visited, &GraphNode::new("".to_string())) {
-                    return true
-                }
+    pub fn run() {
+        let mut graph = Graph::new();
+        for label in ["S", "A", "B", "T"] {
+            graph.add_node(label.to_string());
+        }
+        graph.add_edge("S".to_string(), "A".to_string()).unwrap();
+        graph.add_edge("S".to_string(), "B".to_string()).unwrap();
+        graph.add_edge("A".to_string(), "B".to_string()).unwrap();
+        graph.add_edge("A".to_string(), "T".to_string()).unwrap();
+        graph.add_edge("B".to_string(), "T".to_string()).unwrap();
+
+        let capacities: HashMap<(&str, &str), i32> = HashMap::from([
+            (("S", "A"), 10),
+            (("S", "B"), 5),
+            (("A", "B"), 15),
+            (("A", "T"), 5),
+            (("B", "T"), 10),
+        ]);
+        let network = FlowNetwork::from_graph(&graph, |from, to| capacities[&(from, to)]).unwrap();
+        let result = network.max_flow("S".to_string(), "T".to_string()).unwrap();
+        println!("max flow: {}", result.value);
+        result.flows.iter().for_each(|edge| {
+            println!("{} -> {}: {}/{}", edge.from, edge.to, edge.flow, edge.capacity);
+        });
+        println!("source side: {:?}", result.source_side);
+        println!("cut: {:?}", result.cut);
+    }
+}
+
+pub mod assignment {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Assignment {
+        // (row, column) pairs sorted by row
+        pub pairs: Vec<(usize, usize)>,
+        pub cost: i64,
+    }
+
+    // Hungarian algorithm with potentials, O(n^2 * m) for n rows and m >= n columns.
+    // Rectangular matrices assign every row or every column, whichever is fewer.
+    pub fn min_cost_assignment(costs: &[Vec<i32>]) -> Assignment {
+        let rows = costs.len();
+        let columns = costs.first().map_or(0, |row| row.len());
+        assert!(
+            costs.iter().all(|row| row.len() == columns),
+            "cost matrix rows must have the same length"
+        );
+        let matrix: Vec<Vec<i64>> = costs
+            .iter()
+            .map(|row| row.iter().map(|&cost| cost as i64).collect())
+            .collect();
+
+        if rows <= columns {
+            solve(&matrix)
+        } else {
+            let transposed: Vec<Vec<i64>> = (0..columns)
+                .map(|column| (0..rows).map(|row| matrix[row][column]).collect())
+                .collect();
+            let assignment = solve(&transposed);
+            let mut pairs: Vec<(usize, usize)> =
+                assignment.pairs.iter().map(|&(column, row)| (row, column)).collect();
+            pairs.sort();
+            Assignment {
+                pairs,
+                cost: assignment.cost,
             }
-            false
         }
+    }
 
-        fn has_cycle_(&self, node: GraphNode, visited: &mut HashSet<String>, parent: &GraphNode) -> bool {
-            visited.insert(node.label.clone());
-            for edge in node.get_edges() {
-                println!("Checking edge {} -> {}", edge.from.label, edge.to.label);
-                if edge.to == *parent {
-                    continue
+    // Requires rows <= columns. Arrays are indexed from 1, column 0 is a virtual column
+    // holding the row currently being added.
+    pub(crate) fn solve(costs: &[Vec<i64>]) -> Assignment {
+        let rows = costs.len();
+        let columns = costs.first().map_or(0, |row| row.len());
+        let mut row_potential = vec![0i64; rows + 1];
+        let mut column_potential = vec![0i64; columns + 1];
+        let mut row_of_column = vec![0usize; columns + 1];
+        let mut previous_column = vec![0usize; columns + 1];
+
+        for row in 1..=rows {
+            row_of_column[0] = row;
+            let mut current_column = 0;
+            let mut min_slack = vec![i64::MAX; columns + 1];
+            let mut used = vec![false; columns + 1];
+            loop {
+                used[current_column] = true;
+                let current_row = row_of_column[current_column];
+                let mut delta = i64::MAX;
+                let mut next_column = 0;
+                for column in 1..=columns {
+                    if used[column] {
+                        continue;
+                    }
+                    let slack = costs[current_row - 1][column - 1]
+                        - row_potential[current_row]
+                        - column_potential[column];
+                    if slack < min_slack[column] {
+                        min_slack[column] = slack;
+                        previous_column[column] = current_column;
+                    }
+                    if min_slack[column] < delta {
+                        delta = min_slack[column];
+                        next_column = column;
+                    }
                 }
-
-                if visited.contains(&edge.to.label) ||
-                    self.has_cycle_(edge.to.clone(), visited, &node) {
-                    return true
+                for column in 0..=columns {
+                    if used[column] {
+                        row_potential[row_of_column[column]] += delta;
+                        column_potential[column] -= delta;
+                    } else {
+                        min_slack[column] -= delta;
+                    }
+                }
+                current_column = next_column;
+                if row_of_column[current_column] == 0 {
+                    break;
                 }
             }
-            return false
-        }
 
-        pub fn get_minimum_spanning_tree(&self) -> Self {
-            let mut tree = WeightedGraphs::new();
-            if self.nodes.is_empty() {
-                return tree
+            // Flip the alternating path back to the virtual column
+            while current_column != 0 {
+                let previous = previous_column[current_column];
+                row_of_column[current_column] = row_of_column[previous];
+                current_column = previous;
             }
-            let start_node = self.nodes.values().next().unwrap().clone();
-            let mut edges = priority_queue::PriorityQueue::new();
-            start_node.get_edges().iter().for_each(|edge| {
-                edges.push(edge.clone(), Reverse(edge.weight));
-            });
+        }
 
-            if edges.is_empty() {
-                return tree
-            }
+        let mut pairs: Vec<(usize, usize)> = (1..=columns)
+            .filter(|&column| row_of_column[column] != 0)
+            .map(|column| (row_of_column[column] - 1, column - 1))
+            .collect();
+        pairs.sort();
+        let cost = pairs.iter().map(|&(row, column)| costs[row][column]).sum();
+        Assignment { pairs, cost }
+    }
 
+    pub fn run() {
+        let costs = vec![
+            vec![9, 2, 7, 8],
+            vec![6, 4, 3, 7],
+            vec![5, 8, 1, 8],
+            vec![7, 6, 9, 4],
+        ];
+        println!("{:?}", min_cost_assignment(&costs));
+
+        let costs = vec![vec![4, 1], vec![2, 0], vec![3, 5]];
+        println!("{:?}", min_cost_assignment(&costs));
+    }
 
-            tree.add_node(start_node.label.clone());
+}
 
-            while tree.nodes.len() < self.nodes.len() {
-                let (min_edge, weight) = edges.pop().unwrap();
-                println!("Dequeue edge {}", min_edge.to_string());
-                let next_node = &min_edge.to;
-                if tree.contains_node(&next_node.label) {
-                    continue
-                }
+pub mod suffix_array {
+    use std::cmp::Ordering;
 
-                tree.add_node(next_node.label.clone());
-                tree.add_edge(min_edge.from.label.clone(), next_node.label.clone(), min_edge.weight);
-                next_node.get_edges().iter().for_each(|edge| {
-                    if !tree.contains_node(&edge.to.label) {
-                        edges.push(edge.clone(), Reverse(edge.weight));
-                    }
-                });
-            }
+    // Suffixes of the text in sorted order, with the LCP array alongside:
+    // lcp[i] is the length of the common prefix of suffixes[i - 1] and suffixes[i], lcp[0] = 0
+    #[derive(Debug, Clone)]
+    pub struct SuffixArray<T> {
+        text: Vec<T>,
+        suffixes: Vec<usize>,
+        lcp: Vec<usize>,
+    }
 
-            tree
+    impl<T: Ord> SuffixArray<T> {
+        pub fn new(text: Vec<T>) -> Self {
+            let suffixes = sort_suffixes(&text);
+            let lcp = lcp_array(&text, &suffixes);
+            Self { text, suffixes, lcp }
+        }
 
+        pub fn len(&self) -> usize {
+            self.text.len()
         }
 
-        pub fn contains_node(&self, label: &String) -> bool {
-            self.nodes.contains_key(label)
+        pub fn is_empty(&self) -> bool {
+            self.text.is_empty()
+        }
+
+        pub fn suffixes(&self) -> &[usize] {
+            &self.suffixes
+        }
+
+        pub fn lcp(&self) -> &[usize] {
+            &self.lcp
+        }
+
+        // Start positions of every occurrence of the pattern, in increasing order
+        pub fn find_all(&self, pattern: &[T]) -> Vec<usize> {
+            // Suffixes starting with the pattern form one contiguous block of the array
+            let first = self
+                .suffixes
+                .partition_point(|&start| self.compare_prefix(start, pattern) == Ordering::Less);
+            let last = first
+                + self.suffixes[first..]
+                    .partition_point(|&start| self.compare_prefix(start, pattern) == Ordering::Equal);
+            let mut positions = self.suffixes[first..last].to_vec();
+            positions.sort();
+            positions
+        }
+
+        fn compare_prefix(&self, start: usize, pattern: &[T]) -> Ordering {
+            let end = (start + pattern.len()).min(self.text.len());
+            self.text[start..end].cmp(pattern)
+        }
+
+        // Longest substring that occurs at least twice, occurrences may overlap
+        pub fn longest_repeated_substring(&self) -> Option<&[T]> {
+            let (index, &length) = self
+                .lcp
+                .iter()
+                .enumerate()
+                .max_by_key(|&(index, length)| (length, std::cmp::Reverse(index)))?;
+            if length == 0 {
+                return None;
+            }
+            let start = self.suffixes[index];
+            Some(&self.text[start..start + length])
+        }
+
+        // Every suffix contributes its prefixes that are not shared with the previous suffix
+        pub fn distinct_substrings(&self) -> usize {
+            let n = self.text.len();
+            n * (n + 1) / 2 - self.lcp.iter().sum::<usize>()
         }
     }
 
-    pub fn run () {
-        // let mut graph = WeightedGraphs::new();
-        // graph.add_node("A".to_string());
-        // graph.add_node("B".to_string());
-        // graph.add_node("C".to_string());
-        // graph.add_edge("A".to_string(), "B".to_string(), 3);
-        // graph.add_edge("A".to_string(), "C".to_string(), 2);
-        // println!("{}", graph.to_string());
+    impl From<&str> for SuffixArray<char> {
+        fn from(text: &str) -> Self {
+            SuffixArray::new(text.chars().collect())
+        }
+    }
 
-        let mut graph = WeightedGraphs::new();
-        graph.add_node("A".to_string());
-        graph.add_node("B".to_string());
-        graph.add_node("C".to_string());
-        graph.add_edge("A".to_string(), "B".to_string(), 1);
-        graph.add_edge("B".to_string(), "C".to_string(), 2);
-        graph.add_edge("A".to_string(), "C".to_string(), 10);
-        println!("{}", graph.to_string());
-        graph.get_edges("B".to_string());
-        println!();
-        println!("shortest path: {}", graph.get_shortest_path("A".to_string(), "C".to_string()).to_string());
+    // Prefix doubling, O(n log^2 n): after each round suffixes are ranked by their
+    // first 2k symbols, using the ranks of the two halves of length k as the sort key
+    fn sort_suffixes<T: Ord>(text: &[T]) -> Vec<usize> {
+        let n = text.len();
+        let mut suffixes: Vec<usize> = (0..n).collect();
+        suffixes.sort_by(|&first, &second| text[first].cmp(&text[second]));
+
+        let mut rank = vec![0; n];
+        for i in 1..n {
+            let (previous, current) = (suffixes[i - 1], suffixes[i]);
+            rank[current] = rank[previous] + usize::from(text[previous] != text[current]);
+        }
+
+        let mut k = 1;
+        let mut next_rank = vec![0; n];
+        while k < n && rank[suffixes[n - 1]] < n - 1 {
+            // Suffixes shorter than k sort before every suffix sharing their first half
+            let key = |start: usize| (rank[start], rank.get(start + k).map(|&r| r + 1).unwrap_or(0));
+            suffixes.sort_by_key(|&start| key(start));
+            next_rank[suffixes[0]] = 0;
+            for i in 1..n {
+                next_rank[suffixes[i]] =
+                    next_rank[suffixes[i - 1]] + usize::from(key(suffixes[i - 1]) != key(suffixes[i]));
+            }
+            std::mem::swap(&mut rank, &mut next_rank);
+            k *= 2;
+        }
+        suffixes
+    }
 
-        // let mut graph = WeightedGraphs::new();
-        // graph.add_node("A".to_string());
-        // graph.add_node("B".to_string());
-        // graph.add_node("C".to_string());
-        // graph.add_edge("A".to_string(), "B".to_string(), 0);
-        // graph.add_edge("B".to_string(), "C".to_string(), 0);
-        // graph.add_edge("C".to_string(), "A".to_string(), 0);
-        // // graph.add_edge("C".to_string(), "A".to_string(), 10);
-        // println!("{}", graph.to_string());
-        // println!("{}", graph.has_cycle());
+    // Kasai's algorithm, O(n): the common prefix with the preceding suffix shrinks by
+    // at most one when moving from a suffix to the one starting a position later
+    fn lcp_array<T: Ord>(text: &[T], suffixes: &[usize]) -> Vec<usize> {
+        let n = text.len();
+        let mut rank = vec![0; n];
+        for (index, &start) in suffixes.iter().enumerate() {
+            rank[start] = index;
+        }
+
+        let mut lcp = vec![0; n];
+        let mut length = 0;
+        for start in 0..n {
+            if rank[start] == 0 {
+                length = 0;
+                continue;
+            }
+            let previous = suffixes[rank[start] - 1];
+            while start + length < n && previous + length < n && text[start + length] == text[previous + length] {
+                length += 1;
+            }
+            lcp[rank[start]] = length;
+            length = length.saturating_sub(1);
+        }
+        lcp
+    }
 
-        let mut graph = WeightedGraphs::new();
-        graph.add_node("A".to_string());
-        graph.add_node("B".to_string());
-        graph.add_node("C".to_string());
-        graph.add_node("D".to_string());
-        graph.add_edge("A".to_string(), "B".to_string(), 3);
-        graph.add_edge("B".to_string(), "D".to_string(), 4);
-        graph.add_edge("C".to_string(), "D".to_string(), 5);
-        graph.add_edge("A".to_string(), "C".to_string(), 1);
-        graph.add_edge("B".to_string(), "C".to_string(), 2);
-        // println!("{}", graph.to_string());
-        let tree = graph.get_minimum_spanning_tree();
-        println!("{}", tree.to_string());
+    pub fn run() {
+        let text = SuffixArray::from("banana");
+        println!("{:?}", text.suffixes());
+        println!("{:?}", text.lcp());
+        println!("{:?}", text.find_all(&['a', 'n', 'a']));
+        println!("{:?}", text.longest_repeated_substring().map(|s| s.iter().collect::<String>()));
+        println!("{}", text.distinct_substrings());
+
+        let lines = SuffixArray::new(vec![3, 1, 4, 1, 5, 3, 1, 4]);
+        println!("{:?}", lines.longest_repeated_substring());
     }
 }
-